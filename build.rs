@@ -1,19 +1,7 @@
-fn main() {
-    println!("cargo:rerun-if-env-changed=APP_SERVER_URL");
-    println!("cargo:rerun-if-env-changed=APP_TITLE");
-    println!("cargo:rerun-if-env-changed=APP_TOKEN");
-    println!("cargo:rerun-if-env-changed=IDENTITY_CLIENT_ID");
-    println!("cargo:rerun-if-env-changed=IDENTITY_CLIENT_PROVIDER_APP_URL");
-
-    let app_server_url = std::env::var("APP_SERVER_URL").unwrap_or_default();
-    let app_title = std::env::var("APP_TITLE").unwrap_or("Mango³".to_owned());
-    let app_token = std::env::var("APP_TOKEN").unwrap_or_default();
-    let identity_client_id = std::env::var("IDENTITY_CLIENT_ID").unwrap_or_default();
-    let identity_client_provider_app_url = std::env::var("IDENTITY_CLIENT_PROVIDER_APP_URL").unwrap_or_default();
+#[allow(dead_code)]
+#[path = "src/build/settings.rs"]
+mod settings;
 
-    println!("cargo:rustc-env=APP_SERVER_URL={app_server_url}");
-    println!("cargo:rustc-env=APP_TITLE={app_title}");
-    println!("cargo:rustc-env=APP_TOKEN={app_token}");
-    println!("cargo:rustc-env=IDENTITY_CLIENT_ID={identity_client_id}");
-    println!("cargo:rustc-env=IDENTITY_CLIENT_PROVIDER_APP_URL={identity_client_provider_app_url}");
+fn main() {
+    settings::setup_build_env();
 }
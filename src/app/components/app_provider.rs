@@ -1,5 +1,6 @@
 use dioxus::prelude::*;
 
+use crate::app::config::CLIENT_CONFIG;
 use crate::app::icons::Mango3Icon;
 use crate::app::spinner_is_active;
 
@@ -8,7 +9,7 @@ pub struct AppTitle(pub String);
 
 #[component]
 pub fn AppProvider(children: Element, #[props(optional)] is_starting: ReadSignal<bool>) -> Element {
-    let mut app_title = env!("APP_TITLE").to_owned();
+    let mut app_title = CLIENT_CONFIG.app_title.to_owned();
 
     if cfg!(debug_assertions) {
         app_title += " (dev)";
@@ -0,0 +1,74 @@
+use dioxus::prelude::*;
+
+use crate::app::hooks::use_resource_with_spinner;
+use crate::app::{job_status, sleep};
+use crate::job_status::{JobState, JobStatusInfo};
+
+#[component]
+pub fn JobStatus(
+    task_id: ReadSignal<String>,
+    #[props(default = 2000)] poll_interval: u64,
+    #[props(optional)] class: String,
+    #[props(optional)] on_finished: Callback<JobStatusInfo>,
+) -> Element {
+    let mut status = use_resource_with_spinner("job-status", move || async move { job_status(task_id()).await });
+
+    use_effect(move || {
+        let finished = status
+            .read()
+            .as_ref()
+            .map(|status| status.as_ref().map_or(true, |status| status.state.is_finished()));
+
+        match finished {
+            Some(true) => {
+                if let Some(Ok(status)) = status.peek().as_ref() {
+                    on_finished.call(status.clone());
+                }
+            }
+            Some(false) => {
+                spawn(async move {
+                    sleep(poll_interval).await;
+                    status.restart();
+                });
+            }
+            None => {}
+        }
+    });
+
+    let info = match status.read().as_ref().cloned() {
+        Some(Ok(info)) => info,
+        Some(Err(_)) => {
+            return rsx! {
+                div { class: format!("job-status {class}"),
+                    span { class: "badge badge-error", "Unavailable" }
+                }
+            };
+        }
+        None => {
+            return rsx! {
+                div { class: format!("job-status {class}"), "Queued" }
+            };
+        }
+    };
+
+    let state_class = match info.state {
+        JobState::Queued => "badge-neutral",
+        JobState::Running => "badge-info",
+        JobState::Succeeded => "badge-success",
+        JobState::Failed => "badge-error",
+    };
+
+    rsx! {
+        div { class: format!("job-status {class}"),
+            span { class: format!("badge {state_class}"), "{info.state}" }
+
+            if info.attempts > 1 {
+                span { class: "job-status-attempts", "Attempt {info.attempts}" }
+            }
+
+            if let Some(error) = info.error {
+                div { class: "job-status-error", {error} }
+            }
+        }
+    }
+}
@@ -5,11 +5,13 @@ use super::icons::Mango3Icon;
 
 mod app_provider;
 mod form;
+mod job_status;
 mod logo;
 mod modal;
 
 pub use app_provider::*;
 pub use form::*;
+pub use job_status::*;
 pub use logo::*;
 pub use modal::*;
 
@@ -0,0 +1,182 @@
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+crate::include_client_config!();
+
+static RUNTIME_CLIENT_CONFIG: OnceLock<RuntimeClientConfig> = OnceLock::new();
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RuntimeClientConfig {
+    pub app_server_url: Option<String>,
+    pub app_token: Option<String>,
+    pub identity_client_provider_app_url: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum RuntimeConfigSource {
+    #[cfg(feature = "web")]
+    Meta(String),
+    #[cfg(feature = "web")]
+    Url(String),
+    #[cfg(not(feature = "web"))]
+    File(std::path::PathBuf),
+}
+
+#[cfg(feature = "web")]
+impl Default for RuntimeConfigSource {
+    fn default() -> Self {
+        Self::Url("/config.json".to_owned())
+    }
+}
+
+#[cfg(not(feature = "web"))]
+impl Default for RuntimeConfigSource {
+    fn default() -> Self {
+        Self::File("config.json".into())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum RuntimeConfigError {
+    NotFound,
+    Unavailable(String),
+    Invalid(String),
+}
+
+impl std::fmt::Display for RuntimeConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "Runtime config not found"),
+            Self::Unavailable(err) => write!(f, "Runtime config could not be loaded: {err}"),
+            Self::Invalid(err) => write!(f, "Runtime config is invalid: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeConfigError {}
+
+#[cfg(feature = "web")]
+impl RuntimeConfigSource {
+    pub async fn load(&self) -> Result<RuntimeClientConfig, RuntimeConfigError> {
+        use wasm_bindgen_futures::JsFuture;
+        use web_sys::wasm_bindgen::JsCast;
+
+        let unavailable = |err: web_sys::wasm_bindgen::JsValue| RuntimeConfigError::Unavailable(format!("{err:?}"));
+        let window = web_sys::window().ok_or_else(|| RuntimeConfigError::Unavailable("No window".to_owned()))?;
+
+        let content = match self {
+            Self::Meta(name) => window
+                .document()
+                .ok_or_else(|| RuntimeConfigError::Unavailable("No document".to_owned()))?
+                .query_selector(&format!("meta[name=\"{name}\"]"))
+                .map_err(unavailable)?
+                .ok_or(RuntimeConfigError::NotFound)?
+                .get_attribute("content")
+                .ok_or(RuntimeConfigError::NotFound)?,
+            Self::Url(url) => {
+                let response: web_sys::Response = JsFuture::from(window.fetch_with_str(url))
+                    .await
+                    .map_err(unavailable)?
+                    .dyn_into()
+                    .map_err(unavailable)?;
+
+                if response.status() == 404 {
+                    return Err(RuntimeConfigError::NotFound);
+                }
+
+                if !response.ok() {
+                    return Err(RuntimeConfigError::Unavailable(format!(
+                        "{url} responded with status {}",
+                        response.status()
+                    )));
+                }
+
+                JsFuture::from(response.text().map_err(unavailable)?)
+                    .await
+                    .map_err(unavailable)?
+                    .as_string()
+                    .ok_or_else(|| RuntimeConfigError::Invalid("Response body is not text".to_owned()))?
+            }
+        };
+
+        parse_runtime_config(&content)
+    }
+}
+
+#[cfg(not(feature = "web"))]
+impl RuntimeConfigSource {
+    pub fn load(&self) -> Result<RuntimeClientConfig, RuntimeConfigError> {
+        let content = match self {
+            Self::File(path) => std::fs::read_to_string(path).map_err(|err| match err.kind() {
+                std::io::ErrorKind::NotFound => RuntimeConfigError::NotFound,
+                _ => RuntimeConfigError::Unavailable(format!("{}: {err}", path.display())),
+            })?,
+        };
+
+        parse_runtime_config(&content)
+    }
+}
+
+fn parse_runtime_config(content: &str) -> Result<RuntimeClientConfig, RuntimeConfigError> {
+    serde_json::from_str(content).map_err(|err| RuntimeConfigError::Invalid(err.to_string()))
+}
+
+pub fn runtime_config() -> Option<&'static RuntimeClientConfig> {
+    RUNTIME_CLIENT_CONFIG.get()
+}
+
+pub fn set_runtime_config(config: RuntimeClientConfig) -> bool {
+    RUNTIME_CLIENT_CONFIG.set(config).is_ok()
+}
+
+pub fn app_server_url() -> &'static str {
+    runtime_config()
+        .and_then(|config| config.app_server_url.as_deref())
+        .unwrap_or(CLIENT_CONFIG.app_server_url)
+}
+
+pub fn app_token() -> &'static str {
+    runtime_config()
+        .and_then(|config| config.app_token.as_deref())
+        .unwrap_or(CLIENT_CONFIG.app_token)
+}
+
+pub fn identity_client_provider_app_url() -> &'static str {
+    runtime_config()
+        .and_then(|config| config.identity_client_provider_app_url.as_deref())
+        .unwrap_or(CLIENT_CONFIG.identity_client_provider_app_url)
+}
+
+#[cfg(all(test, not(feature = "web")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_distinguishes_missing_and_invalid_files() {
+        let dir = std::env::temp_dir().join(format!("sdk-runtime-config-{}", std::process::id()));
+        let path = dir.join("config.json");
+
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert!(matches!(
+            RuntimeConfigSource::File(dir.join("missing.json")).load(),
+            Err(RuntimeConfigError::NotFound)
+        ));
+
+        std::fs::write(&path, "{ not json").unwrap();
+
+        assert!(matches!(
+            RuntimeConfigSource::File(path.clone()).load(),
+            Err(RuntimeConfigError::Invalid(_))
+        ));
+
+        std::fs::write(&path, r#"{ "app_token": "token" }"#).unwrap();
+
+        let config = RuntimeConfigSource::File(path).load().unwrap();
+
+        assert_eq!(config.app_token.as_deref(), Some("token"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -4,15 +4,17 @@ use std::time::Duration;
 
 use dioxus::fullstack::{get_request_headers, set_request_headers};
 use dioxus::prelude::*;
-use http::header::AUTHORIZATION;
+use http::header::{AUTHORIZATION, InvalidHeaderValue};
 use http::{HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
 use validator::ValidationErrors;
 
+use crate::constants::X_REQUEST_ID;
 #[cfg(all(feature = "identity-client", feature = "server"))]
 use crate::core::config::IDENTITY_CLIENT_CONFIG;
 
 pub mod components;
+pub mod config;
 pub mod hooks;
 pub mod icons;
 pub mod serv_fn;
@@ -29,6 +31,7 @@ impl From<Error> for ActionError {
         Self {
             message: error.to_string(),
             details: ValidationErrors::default(),
+            code: 500,
         }
     }
 }
@@ -62,6 +65,10 @@ pub fn remove_request_bearer() {
     remove_request_header(AUTHORIZATION);
 }
 
+pub fn remove_request_id() {
+    remove_request_header(X_REQUEST_ID);
+}
+
 pub fn remove_request_header(name: HeaderName) {
     let mut headers = get_request_headers();
 
@@ -74,6 +81,12 @@ pub fn set_request_bearer(token: &str) {
     set_request_header(AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
 }
 
+pub fn set_request_id(request_id: &str) -> Result<(), InvalidHeaderValue> {
+    set_request_header(X_REQUEST_ID, HeaderValue::from_str(request_id)?);
+
+    Ok(())
+}
+
 pub fn set_request_header(name: HeaderName, value: HeaderValue) {
     let mut headers = get_request_headers();
 
@@ -84,16 +97,62 @@ pub fn set_request_header(name: HeaderName, value: HeaderValue) {
 
 pub fn launch(app: fn() -> Element) {
     #[cfg(not(feature = "server"))]
+    setup_client();
+
+    dioxus::launch(app)
+}
+
+pub fn launch_with_runtime_config(app: fn() -> Element, source: config::RuntimeConfigSource) {
+    #[cfg(feature = "server")]
+    {
+        let _ = source;
+
+        dioxus::launch(app)
+    }
+
+    #[cfg(all(feature = "web", not(feature = "server")))]
+    wasm_bindgen_futures::spawn_local(async move {
+        set_loaded_runtime_config(source.load().await);
+
+        launch(app)
+    });
+
+    #[cfg(not(any(feature = "web", feature = "server")))]
     {
-        use crate::constants::X_APP_TOKEN;
+        set_loaded_runtime_config(source.load());
+
+        launch(app)
+    }
+}
+
+#[cfg(not(feature = "server"))]
+fn set_loaded_runtime_config(result: Result<config::RuntimeClientConfig, config::RuntimeConfigError>) {
+    match result {
+        Ok(runtime_config) => {
+            config::set_runtime_config(runtime_config);
+        }
+        Err(config::RuntimeConfigError::NotFound) => {
+            dioxus::logger::tracing::debug!("Runtime config not found, using build time config");
+        }
+        Err(err) => {
+            dioxus::logger::tracing::warn!("{err}, using build time config");
+        }
+    }
+}
+
+#[cfg(not(feature = "server"))]
+fn setup_client() {
+    use crate::constants::X_APP_TOKEN;
 
-        #[cfg(not(feature = "web"))]
-        dioxus::fullstack::set_server_url(env!("APP_SERVER_URL"));
+    #[cfg(not(feature = "web"))]
+    dioxus::fullstack::set_server_url(config::app_server_url());
 
-        set_request_header(X_APP_TOKEN, env!("APP_TOKEN").parse().unwrap());
+    #[cfg(feature = "web")]
+    if let Some(server_url) = config::runtime_config().and_then(|config| config.app_server_url.as_deref()) {
+        dioxus::fullstack::set_server_url(server_url);
     }
 
-    dioxus::launch(app)
+    set_request_header(X_APP_TOKEN, config::app_token().parse().unwrap());
 }
 
 #[cfg(feature = "web")]
@@ -138,7 +197,7 @@ pub fn identity_provider_app_url() -> url::Url {
     return IDENTITY_CLIENT_CONFIG.provider_app_url();
 
     #[cfg(not(feature = "server"))]
-    env!("IDENTITY_CLIENT_PROVIDER_APP_URL")
+    config::identity_client_provider_app_url()
         .parse()
         .expect("Could not parse Identity client provider app URL")
 }
@@ -151,7 +210,7 @@ pub fn identity_authorize_url() -> url::Url {
     url.set_query(Some(&format!("client_id={}", IDENTITY_CLIENT_CONFIG.id())));
 
     #[cfg(not(feature = "server"))]
-    url.set_query(Some(&format!("client_id={}", env!("IDENTITY_CLIENT_ID"))));
+    url.set_query(Some(&format!("client_id={}", config::CLIENT_CONFIG.identity_client_id)));
 
     url
 }
@@ -6,8 +6,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use validator::ValidationErrors;
 
+use crate::job_status::JobStatusInfo;
+
 #[cfg(feature = "server")]
-use dioxus::fullstack::AsStatusCode;
+use dioxus::fullstack::{AsStatusCode, FullstackContext};
 #[cfg(feature = "server")]
 use headers::authorization::{Bearer, Credentials};
 #[cfg(feature = "server")]
@@ -18,7 +20,9 @@ use http::header::AUTHORIZATION;
 #[cfg(feature = "server")]
 use crate::constants::HEADER_APP_TOKEN;
 #[cfg(feature = "server")]
-use crate::core::config::APP_CONFIG;
+use crate::core::app_token::{VerifiedAppToken, verify_app_token};
+#[cfg(feature = "server")]
+use crate::server::ClientInfo;
 
 pub type ActionResult = Result<ActionSuccess, ActionError>;
 
@@ -28,6 +32,12 @@ pub type ServFnResult<T = ()> = Result<T, HttpError>;
 pub struct ActionError {
     pub(crate) message: String,
     pub(crate) details: ValidationErrors,
+    #[serde(default = "default_action_error_code")]
+    pub(crate) code: u16,
+}
+
+const fn default_action_error_code() -> u16 {
+    422
 }
 
 #[cfg(feature = "server")]
@@ -36,6 +46,7 @@ impl ActionError {
         Self {
             message: message.to_owned(),
             details: details.unwrap_or_default(),
+            code: default_action_error_code(),
         }
     }
 
@@ -54,13 +65,15 @@ impl Display for ActionError {
 impl From<ServerFnError> for ActionError {
     fn from(error: ServerFnError) -> Self {
         match error {
-            ServerFnError::ServerError { message, .. } => Self {
+            ServerFnError::ServerError { message, code, .. } => Self {
                 message,
                 details: ValidationErrors::default(),
+                code,
             },
             _ => Self {
                 message: error.to_string(),
                 details: ValidationErrors::default(),
+                code: 500,
             },
         }
     }
@@ -72,6 +85,7 @@ impl From<HttpError> for ActionError {
         Self {
             message: error.message.unwrap_or_else(|| error.status.to_string()),
             details: ValidationErrors::default(),
+            code: error.status.as_u16(),
         }
     }
 }
@@ -82,6 +96,7 @@ impl From<serde_json::Error> for ActionError {
         Self {
             message: error.to_string(),
             details: ValidationErrors::default(),
+            code: 500,
         }
     }
 }
@@ -89,7 +104,7 @@ impl From<serde_json::Error> for ActionError {
 #[cfg(feature = "server")]
 impl AsStatusCode for ActionError {
     fn as_status_code(&self) -> StatusCode {
-        StatusCode::UNPROCESSABLE_ENTITY
+        StatusCode::from_u16(self.code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
     }
 }
 
@@ -113,8 +128,30 @@ impl ActionSuccess {
     }
 }
 
+#[get("/api/jobs/{task_id}", headers: http::HeaderMap)]
+pub async fn job_status(task_id: String) -> ServFnResult<JobStatusInfo> {
+    headers.require_app_token()?;
+
+    let status = crate::job_status::job_status_query().and_then(|query| query.job_status(&task_id));
+
+    if let (Some(status), Some(authorizer)) = (status, crate::job_status::job_status_authorizer())
+        && authorizer.authorize(headers, status.clone()).await
+    {
+        return Ok(status);
+    }
+
+    HttpError::not_found("Job not found")
+}
+
+#[cfg(feature = "server")]
+pub async fn client_info() -> ClientInfo {
+    FullstackContext::extract::<ClientInfo, _>().await.unwrap_or_default()
+}
+
 #[cfg(feature = "server")]
 pub trait HeaderMapExt {
+    fn app_token(&self) -> ServFnResult<VerifiedAppToken>;
+
     fn bearer(&self) -> ServFnResult<Bearer>;
 
     fn require_app_token(&self) -> ServFnResult;
@@ -122,6 +159,15 @@ pub trait HeaderMapExt {
 
 #[cfg(feature = "server")]
 impl HeaderMapExt for HeaderMap {
+    fn app_token(&self) -> ServFnResult<VerifiedAppToken> {
+        let app_token = self
+            .get(HEADER_APP_TOKEN)
+            .and_then(|value| value.to_str().ok())
+            .or_forbidden("Forbidden")?;
+
+        verify_app_token(app_token).or_forbidden("Forbidden")
+    }
+
     fn bearer(&self) -> ServFnResult<Bearer> {
         let value = self.get(AUTHORIZATION).or_unauthorized("Unauthorized")?;
 
@@ -129,16 +175,6 @@ impl HeaderMapExt for HeaderMap {
     }
 
     fn require_app_token(&self) -> ServFnResult {
-        let app_token = self
-            .get(HEADER_APP_TOKEN)
-            .and_then(|value| value.to_str().ok())
-            .or_forbidden("Forbidden")?
-            .to_owned();
-
-        if app_token == APP_CONFIG.token || APP_CONFIG.old_tokens.contains(&app_token) {
-            Ok(())
-        } else {
-            HttpError::forbidden("Forbidden")
-        }
+        self.app_token().map(|_| ())
     }
 }
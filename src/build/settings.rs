@@ -0,0 +1,203 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+pub const CLIENT_SETTINGS: &[BuildSetting] = &[
+    BuildSetting::url("APP_SERVER_URL"),
+    BuildSetting::string("APP_TITLE").with_default("Mango³"),
+    BuildSetting::string("APP_TOKEN").secret(),
+    BuildSetting::uuid("IDENTITY_CLIENT_ID"),
+    BuildSetting::url("IDENTITY_CLIENT_PROVIDER_APP_URL"),
+];
+
+pub fn setup_build_env() {
+    BuildEnv::new().setup();
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BuildSettingKind {
+    String,
+    Url,
+    Uuid,
+}
+
+#[derive(Clone, Copy)]
+pub struct BuildSetting {
+    env_var: &'static str,
+    kind: BuildSettingKind,
+    default: &'static str,
+    is_secret: bool,
+}
+
+impl BuildSetting {
+    pub const fn new(env_var: &'static str, kind: BuildSettingKind) -> Self {
+        Self {
+            env_var,
+            kind,
+            default: "",
+            is_secret: false,
+        }
+    }
+
+    pub const fn string(env_var: &'static str) -> Self {
+        Self::new(env_var, BuildSettingKind::String)
+    }
+
+    pub const fn url(env_var: &'static str) -> Self {
+        Self::new(env_var, BuildSettingKind::Url)
+    }
+
+    pub const fn uuid(env_var: &'static str) -> Self {
+        Self::new(env_var, BuildSettingKind::Uuid)
+    }
+
+    pub const fn with_default(mut self, default: &'static str) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub const fn secret(mut self) -> Self {
+        self.is_secret = true;
+        self
+    }
+
+    fn field_name(&self) -> String {
+        let field_name = self.env_var.to_lowercase();
+
+        assert!(
+            !field_name.is_empty()
+                && !field_name.starts_with(|char: char| char.is_ascii_digit())
+                && field_name
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || char == '_'),
+            "Invalid build setting name: {}",
+            self.env_var
+        );
+
+        field_name
+    }
+
+    fn value(&self) -> String {
+        let value = std::env::var(self.env_var).unwrap_or_else(|_| self.default.to_owned());
+
+        if value.is_empty() {
+            return value;
+        }
+
+        let result = match self.kind {
+            BuildSettingKind::String => Ok(()),
+            BuildSettingKind::Url => url::Url::parse(&value).map(|_| ()).map_err(|error| error.to_string()),
+            BuildSettingKind::Uuid => uuid::Uuid::parse_str(&value)
+                .map(|_| ())
+                .map_err(|error| error.to_string()),
+        };
+
+        if let Err(error) = result {
+            panic!("Invalid value for {}: {error}", self.env_var);
+        }
+
+        value
+    }
+}
+
+pub struct BuildEnv {
+    settings: Vec<BuildSetting>,
+}
+
+impl Default for BuildEnv {
+    fn default() -> Self {
+        Self {
+            settings: CLIENT_SETTINGS.to_vec(),
+        }
+    }
+}
+
+impl BuildEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn setting(mut self, setting: BuildSetting) -> Self {
+        self.settings.retain(|current| current.env_var != setting.env_var);
+        self.settings.push(setting);
+        self
+    }
+
+    pub fn setup(self) {
+        let mut fields = String::new();
+        let mut values = String::new();
+        let mut build_info = String::new();
+
+        for (name, env_var) in [
+            ("package_name", "CARGO_PKG_NAME"),
+            ("package_version", "CARGO_PKG_VERSION"),
+            ("profile", "PROFILE"),
+            ("target", "TARGET"),
+        ] {
+            let value = std::env::var(env_var).unwrap_or_default();
+
+            let _ = writeln!(build_info, "    ({name:?}, {value:?}),");
+        }
+
+        for setting in &self.settings {
+            println!("cargo:rerun-if-env-changed={}", setting.env_var);
+
+            let field_name = setting.field_name();
+            let value = setting.value();
+
+            println!("cargo:rustc-env={}={value}", setting.env_var);
+
+            let _ = writeln!(fields, "    pub {field_name}: &'static str,");
+            let _ = writeln!(values, "    {field_name}: {value:?},");
+
+            if !setting.is_secret {
+                let _ = writeln!(build_info, "    ({field_name:?}, {value:?}),");
+            }
+        }
+
+        let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("Could not get OUT_DIR"));
+
+        std::fs::write(
+            out_dir.join("client_config.rs"),
+            format!(
+                "#[derive(Clone, Copy, Debug)]\npub struct ClientConfig {{\n{fields}}}\n\npub const CLIENT_CONFIG: ClientConfig = ClientConfig {{\n{values}}};\n"
+            ),
+        )
+        .expect("Could not write client config");
+
+        std::fs::write(
+            out_dir.join("build_info.rs"),
+            format!("pub const BUILD_INFO: &[(&str, &str)] = &[\n{build_info}];\n"),
+        )
+        .expect("Could not write build info");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_settings_are_generated_into_client_config() {
+        let out_dir = std::env::temp_dir().join(format!("sdk-build-settings-{}", std::process::id()));
+
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        unsafe {
+            std::env::set_var("OUT_DIR", &out_dir);
+            std::env::set_var("APP_SUPPORT_URL", "https://support.example.com");
+        }
+
+        BuildEnv::new()
+            .setting(BuildSetting::url("APP_SUPPORT_URL"))
+            .setting(BuildSetting::string("APP_THEME").with_default("light"))
+            .setup();
+
+        let client_config = std::fs::read_to_string(out_dir.join("client_config.rs")).unwrap();
+
+        assert!(client_config.contains("pub app_support_url: &'static str,"));
+        assert!(client_config.contains("app_support_url: \"https://support.example.com\","));
+        assert!(client_config.contains("app_theme: \"light\","));
+
+        let _ = std::fs::remove_dir_all(out_dir);
+    }
+}
@@ -1,4 +1,9 @@
-#[cfg(any(feature = "app", feature = "identity-client"))]
+#[cfg(any(
+    feature = "app",
+    feature = "identity-client",
+    feature = "server",
+    feature = "webhooks"
+))]
 use http::HeaderName;
 #[cfg(feature = "core")]
 use std::borrow::Cow;
@@ -21,11 +26,15 @@ pub static ERROR_ALREADY_EXISTS: LazyLock<ValidationError> =
 pub static ERROR_IS_INVALID: LazyLock<ValidationError> =
     LazyLock::new(|| ValidationError::new("invalid").with_message(Cow::Borrowed("Is invalid")));
 
-#[cfg(feature = "app")]
+#[cfg(any(feature = "app", feature = "server"))]
 pub const HEADER_APP_TOKEN: &str = "x-app-token";
 #[cfg(feature = "app")]
 pub const HEADER_AUTHORIZATION: &str = "authorization";
 #[cfg(feature = "server")]
+pub const HEADER_X_FORWARDED_FOR: &str = "x-forwarded-for";
+#[cfg(any(feature = "app", feature = "server"))]
+pub const HEADER_X_REQUEST_ID: &str = "x-request-id";
+#[cfg(feature = "server")]
 pub const HEADER_X_REAL_IP: &str = "x-real-ip";
 #[cfg(feature = "server")]
 pub const HEADER_USER_AGENT: &str = "user-agent";
@@ -35,18 +44,28 @@ pub const RESPONSE_OK: (StatusCode, &str) = (StatusCode::OK, "\"Ok\"");
 #[cfg(feature = "server")]
 pub const RESPONSE_CREATED: (StatusCode, &str) = (StatusCode::CREATED, "\"Created\"");
 #[cfg(feature = "server")]
+#[deprecated(note = "Use `server::ApiError` instead")]
 pub const RESPONSE_BAD_REQUEST: (StatusCode, &str) = (StatusCode::BAD_REQUEST, "\"Bad Request\"");
 #[cfg(feature = "server")]
+#[deprecated(note = "Use `server::ApiError` instead")]
 pub const RESPONSE_FORBIDDEN: (StatusCode, &str) = (StatusCode::FORBIDDEN, "\"Forbidden\"");
 #[cfg(feature = "server")]
+#[deprecated(note = "Use `server::ApiError` instead")]
 pub const RESPONSE_NOT_FOUND: (StatusCode, &str) = (StatusCode::NOT_FOUND, "\"Not Found\"");
 #[cfg(feature = "server")]
+#[deprecated(note = "Use `server::ApiError` instead")]
+pub const RESPONSE_TOO_MANY_REQUESTS: (StatusCode, &str) = (StatusCode::TOO_MANY_REQUESTS, "\"Too Many Requests\"");
+#[cfg(feature = "server")]
+#[deprecated(note = "Use `server::ApiError` instead")]
 pub const RESPONSE_UNAUTHORIZED: (StatusCode, &str) = (StatusCode::UNAUTHORIZED, "\"Unauthorized\"");
 #[cfg(feature = "server")]
+#[deprecated(note = "Use `server::ApiError` instead")]
 pub const RESPONSE_INTERNAL_SERVER_ERROR: (StatusCode, &str) =
     (StatusCode::INTERNAL_SERVER_ERROR, "\"Internal Server Error\"");
 
-#[cfg(feature = "app")]
+#[cfg(any(feature = "app", feature = "server"))]
 pub const X_APP_TOKEN: HeaderName = HeaderName::from_static("x-app-token");
-#[cfg(feature = "identity-client")]
+#[cfg(any(feature = "app", feature = "server"))]
+pub const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+#[cfg(any(feature = "identity-client", feature = "webhooks"))]
 pub const X_WEBHOOK_SIGNATURE: HeaderName = HeaderName::from_static("x-webhook-signature");
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{LazyLock, Mutex};
+
+use serde::Serialize;
+use tracing::warn;
+
+use super::config::APP_CONFIG;
+use super::token::{RandomToken, constant_time_eq};
+
+const SEPARATOR: char = ':';
+
+static OLD_APP_TOKEN_USAGE: LazyLock<Mutex<HashMap<AppTokenUsageKey, u64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppToken {
+    client: String,
+    version: String,
+    secret: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AppTokenError {
+    InvalidClient(String),
+    InvalidVersion(String),
+}
+
+impl Display for AppTokenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidClient(client) => write!(f, "Invalid app token client: {client:?}"),
+            Self::InvalidVersion(version) => write!(f, "Invalid app token version: {version:?}"),
+        }
+    }
+}
+
+impl std::error::Error for AppTokenError {}
+
+impl AppToken {
+    pub fn issue(client: &str, version: &str) -> Result<Self, AppTokenError> {
+        if client.is_empty() || client.contains(SEPARATOR) {
+            return Err(AppTokenError::InvalidClient(client.to_owned()));
+        }
+
+        if version.is_empty() || version.contains(SEPARATOR) {
+            return Err(AppTokenError::InvalidVersion(version.to_owned()));
+        }
+
+        Ok(Self {
+            client: client.to_owned(),
+            version: version.to_owned(),
+            secret: RandomToken::url_safe(43).generate(),
+        })
+    }
+
+    pub fn parse(token: &str) -> Option<Self> {
+        let mut parts = token.splitn(3, SEPARATOR);
+        let client = parts.next().filter(|client| !client.is_empty())?;
+        let version = parts.next().filter(|version| !version.is_empty())?;
+        let secret = parts.next().filter(|secret| !secret.is_empty())?;
+
+        Some(Self {
+            client: client.to_owned(),
+            version: version.to_owned(),
+            secret: secret.to_owned(),
+        })
+    }
+
+    pub fn client(&self) -> &str {
+        &self.client
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+impl Display for AppToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{SEPARATOR}{}{SEPARATOR}{}",
+            self.client, self.version, self.secret
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppTokenMatch {
+    Current,
+    Old(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifiedAppToken {
+    pub matched: AppTokenMatch,
+    pub client: Option<String>,
+    pub version: Option<String>,
+}
+
+impl VerifiedAppToken {
+    pub fn is_old(&self) -> bool {
+        matches!(self.matched, AppTokenMatch::Old(_))
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct AppTokenUsageKey {
+    index: usize,
+    client: Option<String>,
+    version: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AppTokenUsage {
+    pub index: usize,
+    pub client: Option<String>,
+    pub version: Option<String>,
+    pub requests: u64,
+}
+
+pub fn verify_app_token(token: &str) -> Option<VerifiedAppToken> {
+    let verified = verify_app_token_with(token, &APP_CONFIG.token, &APP_CONFIG.old_tokens)?;
+
+    if let AppTokenMatch::Old(index) = verified.matched {
+        record_old_app_token_usage(index, &verified);
+    }
+
+    Some(verified)
+}
+
+pub fn verify_app_token_with(token: &str, current: &str, old_tokens: &[String]) -> Option<VerifiedAppToken> {
+    let mut matched = None;
+
+    if !current.is_empty() && constant_time_eq(token.as_bytes(), current.as_bytes()) {
+        matched = Some(AppTokenMatch::Current);
+    }
+
+    for (index, old_token) in old_tokens.iter().enumerate() {
+        if !old_token.is_empty() && constant_time_eq(token.as_bytes(), old_token.as_bytes()) && matched.is_none() {
+            matched = Some(AppTokenMatch::Old(index));
+        }
+    }
+
+    let app_token = AppToken::parse(token);
+
+    Some(VerifiedAppToken {
+        matched: matched?,
+        client: app_token.as_ref().map(|app_token| app_token.client.clone()),
+        version: app_token.map(|app_token| app_token.version),
+    })
+}
+
+pub fn old_app_token_usage() -> Vec<AppTokenUsage> {
+    let usage = OLD_APP_TOKEN_USAGE.lock().expect("Could not lock app token usage");
+
+    let mut usage = usage
+        .iter()
+        .map(|(key, &requests)| AppTokenUsage {
+            index: key.index,
+            client: key.client.clone(),
+            version: key.version.clone(),
+            requests,
+        })
+        .collect::<Vec<_>>();
+
+    usage.sort_by_key(|usage| (usage.index, usage.client.clone(), usage.version.clone()));
+
+    usage
+}
+
+fn record_old_app_token_usage(index: usize, verified: &VerifiedAppToken) {
+    let key = AppTokenUsageKey {
+        index,
+        client: verified.client.clone(),
+        version: verified.version.clone(),
+    };
+
+    let mut usage = OLD_APP_TOKEN_USAGE.lock().expect("Could not lock app token usage");
+    let requests = usage.entry(key).or_default();
+
+    if *requests == 0 {
+        warn!(
+            "Old app token #{index} is still in use by client {} (version {})",
+            verified.client.as_deref().unwrap_or("unknown"),
+            verified.version.as_deref().unwrap_or("unknown")
+        );
+    }
+
+    *requests += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_tokens_round_trip() {
+        let token = AppToken::issue("web", "1.2.0").unwrap();
+        let parsed = AppToken::parse(&token.to_string()).unwrap();
+
+        assert_eq!(parsed, token);
+        assert_eq!(parsed.client(), "web");
+        assert_eq!(parsed.version(), "1.2.0");
+        assert_eq!(token.secret.len(), 43);
+        assert_ne!(token, AppToken::issue("web", "1.2.0").unwrap());
+    }
+
+    #[test]
+    fn invalid_clients_and_versions_are_rejected() {
+        assert_eq!(
+            AppToken::issue("", "1"),
+            Err(AppTokenError::InvalidClient(String::new()))
+        );
+        assert_eq!(
+            AppToken::issue("we:b", "1"),
+            Err(AppTokenError::InvalidClient("we:b".to_owned()))
+        );
+        assert_eq!(
+            AppToken::issue("web", ""),
+            Err(AppTokenError::InvalidVersion(String::new()))
+        );
+        assert_eq!(AppToken::parse("web:1"), None);
+        assert_eq!(AppToken::parse("web::secret"), None);
+    }
+
+    #[test]
+    fn tokens_are_verified_against_current_and_old_tokens() {
+        let current = AppToken::issue("web", "2").unwrap().to_string();
+        let old = [
+            AppToken::issue("web", "1").unwrap().to_string(),
+            "legacy-token".to_owned(),
+        ];
+
+        let verified = verify_app_token_with(&current, &current, &old).unwrap();
+
+        assert_eq!(verified.matched, AppTokenMatch::Current);
+        assert_eq!(verified.client.as_deref(), Some("web"));
+        assert_eq!(verified.version.as_deref(), Some("2"));
+        assert!(!verified.is_old());
+
+        let verified = verify_app_token_with("legacy-token", &current, &old).unwrap();
+
+        assert_eq!(verified.matched, AppTokenMatch::Old(1));
+        assert_eq!(verified.client, None);
+        assert!(verified.is_old());
+
+        assert_eq!(
+            verify_app_token_with(&old[0], &current, &old).unwrap().matched,
+            AppTokenMatch::Old(0)
+        );
+        assert_eq!(verify_app_token_with("", &current, &old), None);
+        assert_eq!(verify_app_token_with("", "", &[String::new()]), None);
+        assert_eq!(verify_app_token_with("unknown", &current, &old), None);
+    }
+
+    #[test]
+    fn old_token_usage_is_counted_per_client_and_version() {
+        let verified = |client: &str, version: &str| VerifiedAppToken {
+            matched: AppTokenMatch::Old(7),
+            client: Some(client.to_owned()),
+            version: Some(version.to_owned()),
+        };
+
+        record_old_app_token_usage(7, &verified("usage-test", "1"));
+        record_old_app_token_usage(7, &verified("usage-test", "1"));
+        record_old_app_token_usage(7, &verified("usage-test", "2"));
+
+        let usage = old_app_token_usage()
+            .into_iter()
+            .filter(|usage| usage.client.as_deref() == Some("usage-test"))
+            .map(|usage| (usage.index, usage.version.unwrap(), usage.requests))
+            .collect::<Vec<_>>();
+
+        assert_eq!(usage, [(7, "1".to_owned(), 2), (7, "2".to_owned(), 1)]);
+    }
+}
@@ -1,12 +1,24 @@
+use std::fmt::Write;
 use std::sync::LazyLock;
 
 use figment::Figment;
 use figment::providers::{Env, Serialized};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+#[cfg(any(feature = "monitor", feature = "server"))]
+use std::time::Duration;
 
 #[cfg(feature = "identity-client")]
 use url::Url;
 
+#[cfg(feature = "mail")]
+use crate::mail::{MailTransport, SmtpTls};
+#[cfg(feature = "monitor")]
+use crate::monitor::LogLevel;
+
 pub fn extract_config_from_env<'a, T>(prefix: &str) -> T
 where
     T: Deserialize<'a> + Serialize + Default,
@@ -17,11 +29,23 @@ where
         .unwrap()
 }
 
-pub static APP_CONFIG: LazyLock<AppConfig> = LazyLock::new(|| extract_config_from_env("APP_"));
+pub static APP_CONFIG: LazyLock<AppConfig> = LazyLock::new(|| extract_config_from_env(AppConfig::PREFIX));
 
 #[cfg(feature = "identity-client")]
 pub(crate) static IDENTITY_CLIENT_CONFIG: LazyLock<IdentityClientConfig> =
-    LazyLock::new(|| extract_config_from_env("IDENTITY_CLIENT_"));
+    LazyLock::new(|| extract_config_from_env(IdentityClientConfig::PREFIX));
+
+#[cfg(feature = "mail")]
+pub static MAIL_CONFIG: LazyLock<MailConfig> = LazyLock::new(|| extract_config_from_env(MailConfig::PREFIX));
+
+#[cfg(feature = "monitor")]
+pub static MONITOR_CONFIG: LazyLock<MonitorConfig> = LazyLock::new(|| extract_config_from_env(MonitorConfig::PREFIX));
+
+#[cfg(feature = "server")]
+pub static SERVER_CONFIG: LazyLock<ServerConfig> = LazyLock::new(|| extract_config_from_env(ServerConfig::PREFIX));
+
+#[cfg(feature = "webhooks")]
+pub static WEBHOOK_CONFIG: LazyLock<WebhookConfig> = LazyLock::new(|| extract_config_from_env(WebhookConfig::PREFIX));
 
 #[derive(Deserialize, Serialize)]
 pub struct AppConfig {
@@ -31,6 +55,22 @@ pub struct AppConfig {
     pub old_tokens: Vec<String>,
 }
 
+impl ConfigSchema for AppConfig {
+    const PREFIX: &'static str = "APP_";
+    const FIELDS: &'static [ConfigField] = &[
+        ConfigField::new("server_url", "string"),
+        ConfigField::new("title", "string"),
+        ConfigField::secret("token", "string"),
+        ConfigField::secret("old_tokens", "list<string>"),
+    ];
+}
+
+impl AppConfig {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -65,6 +105,18 @@ impl Default for IdentityClientConfig {
     }
 }
 
+#[cfg(feature = "identity-client")]
+impl ConfigSchema for IdentityClientConfig {
+    const PREFIX: &'static str = "IDENTITY_CLIENT_";
+    const FIELDS: &'static [ConfigField] = &[
+        ConfigField::new("id", "uuid"),
+        ConfigField::secret("secret", "string"),
+        ConfigField::new("provider_api_url", "url"),
+        ConfigField::new("provider_app_url", "url"),
+        ConfigField::secret("webhook_secret", "string?"),
+    ];
+}
+
 #[cfg(feature = "identity-client")]
 impl IdentityClientConfig {
     pub fn id(&self) -> uuid::Uuid {
@@ -84,3 +136,435 @@ impl IdentityClientConfig {
             .expect("Could not parse Auth client provider App URL")
     }
 }
+
+#[cfg(feature = "mail")]
+#[derive(Clone, Deserialize, Serialize)]
+pub struct MailConfig {
+    pub transport: MailTransport,
+    pub sender: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_tls: SmtpTls,
+    pub file_path: String,
+}
+
+#[cfg(feature = "mail")]
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: MailTransport::Smtp,
+            sender: "no-reply@localhost".to_owned(),
+            smtp_host: "localhost".to_owned(),
+            smtp_port: 587,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_tls: SmtpTls::Starttls,
+            file_path: "mail".to_owned(),
+        }
+    }
+}
+
+#[cfg(feature = "mail")]
+impl ConfigSchema for MailConfig {
+    const PREFIX: &'static str = "MAIL_";
+    const FIELDS: &'static [ConfigField] = &[
+        ConfigField::new("transport", "mail_transport"),
+        ConfigField::new("sender", "mailbox"),
+        ConfigField::new("smtp_host", "string"),
+        ConfigField::new("smtp_port", "integer"),
+        ConfigField::new("smtp_username", "string?"),
+        ConfigField::secret("smtp_password", "string?"),
+        ConfigField::new("smtp_tls", "smtp_tls"),
+        ConfigField::new("file_path", "path"),
+    ];
+}
+
+#[cfg(feature = "monitor")]
+#[derive(Clone, Deserialize, Serialize)]
+pub struct MonitorConfig {
+    pub shutdown_timeout_ms: u64,
+    pub log_idle_events: bool,
+    pub start_log_level: LogLevel,
+    pub engage_log_level: LogLevel,
+    pub idle_log_level: LogLevel,
+    pub custom_log_level: LogLevel,
+    pub error_log_level: LogLevel,
+    pub stop_log_level: LogLevel,
+    pub exit_log_level: LogLevel,
+    pub dead_letter_path: Option<String>,
+}
+
+#[cfg(feature = "monitor")]
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            shutdown_timeout_ms: 5000,
+            log_idle_events: false,
+            start_log_level: LogLevel::Info,
+            engage_log_level: LogLevel::Info,
+            idle_log_level: LogLevel::Debug,
+            custom_log_level: LogLevel::Info,
+            error_log_level: LogLevel::Error,
+            stop_log_level: LogLevel::Info,
+            exit_log_level: LogLevel::Info,
+            dead_letter_path: None,
+        }
+    }
+}
+
+#[cfg(feature = "monitor")]
+impl ConfigSchema for MonitorConfig {
+    const PREFIX: &'static str = "MONITOR_";
+    const FIELDS: &'static [ConfigField] = &[
+        ConfigField::new("shutdown_timeout_ms", "integer"),
+        ConfigField::new("log_idle_events", "boolean"),
+        ConfigField::new("start_log_level", "log_level"),
+        ConfigField::new("engage_log_level", "log_level"),
+        ConfigField::new("idle_log_level", "log_level"),
+        ConfigField::new("custom_log_level", "log_level"),
+        ConfigField::new("error_log_level", "log_level"),
+        ConfigField::new("stop_log_level", "log_level"),
+        ConfigField::new("exit_log_level", "log_level"),
+        ConfigField::new("dead_letter_path", "path?"),
+    ];
+}
+
+#[cfg(feature = "monitor")]
+impl MonitorConfig {
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(self.shutdown_timeout_ms)
+    }
+}
+
+#[cfg(feature = "server")]
+#[derive(Deserialize, Serialize)]
+pub struct ServerConfig {
+    address: String,
+    pub cors_allowed_origins: Vec<String>,
+    pub trusted_proxies: Vec<String>,
+    pub client_ip_header: String,
+    pub shutdown_timeout_ms: u64,
+}
+
+#[cfg(feature = "server")]
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:8080".to_owned(),
+            cors_allowed_origins: Vec::new(),
+            trusted_proxies: vec!["127.0.0.0/8".to_owned(), "::1/128".to_owned()],
+            client_ip_header: crate::constants::HEADER_X_FORWARDED_FOR.to_owned(),
+            shutdown_timeout_ms: 30000,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl ConfigSchema for ServerConfig {
+    const PREFIX: &'static str = "SERVER_";
+    const FIELDS: &'static [ConfigField] = &[
+        ConfigField::new("address", "socket_addr"),
+        ConfigField::new("cors_allowed_origins", "list<string>"),
+        ConfigField::new("trusted_proxies", "list<cidr>"),
+        ConfigField::new("client_ip_header", "string"),
+        ConfigField::new("shutdown_timeout_ms", "integer"),
+    ];
+}
+
+#[cfg(feature = "server")]
+impl ServerConfig {
+    pub fn address(&self) -> SocketAddr {
+        self.address.parse().expect("Could not parse server address")
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(self.shutdown_timeout_ms)
+    }
+}
+
+#[cfg(feature = "webhooks")]
+#[derive(Deserialize, Serialize)]
+pub struct WebhookConfig {
+    pub timeout_ms: u64,
+    pub disable_after_failures: usize,
+    pub delivery_log_size: usize,
+}
+
+#[cfg(feature = "webhooks")]
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 10000,
+            disable_after_failures: 15,
+            delivery_log_size: 100,
+        }
+    }
+}
+
+#[cfg(feature = "webhooks")]
+impl ConfigSchema for WebhookConfig {
+    const PREFIX: &'static str = "WEBHOOK_";
+    const FIELDS: &'static [ConfigField] = &[
+        ConfigField::new("timeout_ms", "integer"),
+        ConfigField::new("disable_after_failures", "integer"),
+        ConfigField::new("delivery_log_size", "integer"),
+    ];
+}
+
+#[cfg(feature = "webhooks")]
+impl WebhookConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+pub fn config_schema() -> Vec<ConfigKey> {
+    config_schemas().into_iter().flatten().collect()
+}
+
+pub fn config_schema_json() -> String {
+    serde_json::to_string_pretty(&config_schema()).expect("Could not serialize config schema")
+}
+
+pub fn config_schema_markdown() -> String {
+    let mut markdown = "| Variable | Type | Default | Secret |\n| --- | --- | --- | --- |\n".to_owned();
+
+    for key in config_schema() {
+        let _ = writeln!(
+            markdown,
+            "| `{}` | {} | `{}` | {} |",
+            key.env_var,
+            key.type_name,
+            key.default,
+            if key.is_secret { "yes" } else { "no" }
+        );
+    }
+
+    markdown
+}
+
+pub fn effective_config() -> Value {
+    Value::Object(
+        effective_sections()
+            .into_iter()
+            .map(|(prefix, effective)| (prefix.to_owned(), effective))
+            .collect(),
+    )
+}
+
+pub fn print_config() {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&effective_config()).expect("Could not serialize config")
+    );
+}
+
+pub fn handle_config_flags() {
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--print-config" => print_config(),
+            "--config-schema" | "--config-schema=markdown" => print!("{}", config_schema_markdown()),
+            "--config-schema=json" => println!("{}", config_schema_json()),
+            _ => continue,
+        }
+
+        std::process::exit(0);
+    }
+}
+
+fn config_schemas() -> Vec<Vec<ConfigKey>> {
+    vec![
+        AppConfig::schema(),
+        #[cfg(feature = "identity-client")]
+        IdentityClientConfig::schema(),
+        #[cfg(feature = "mail")]
+        MailConfig::schema(),
+        #[cfg(feature = "monitor")]
+        MonitorConfig::schema(),
+        #[cfg(feature = "server")]
+        ServerConfig::schema(),
+        #[cfg(feature = "webhooks")]
+        WebhookConfig::schema(),
+    ]
+}
+
+fn effective_sections() -> Vec<(&'static str, Value)> {
+    vec![
+        effective_section(&*APP_CONFIG),
+        #[cfg(feature = "identity-client")]
+        effective_section(&*IDENTITY_CLIENT_CONFIG),
+        #[cfg(feature = "mail")]
+        effective_section(&*MAIL_CONFIG),
+        #[cfg(feature = "monitor")]
+        effective_section(&*MONITOR_CONFIG),
+        #[cfg(feature = "server")]
+        effective_section(&*SERVER_CONFIG),
+        #[cfg(feature = "webhooks")]
+        effective_section(&*WEBHOOK_CONFIG),
+    ]
+}
+
+fn effective_section<T: ConfigSchema>(config: &T) -> (&'static str, Value) {
+    (T::PREFIX, config.redacted())
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ConfigKey {
+    pub env_var: String,
+    pub type_name: &'static str,
+    pub default: Value,
+    pub is_secret: bool,
+}
+
+pub struct ConfigField {
+    name: &'static str,
+    type_name: &'static str,
+    is_secret: bool,
+}
+
+impl ConfigField {
+    pub const fn new(name: &'static str, type_name: &'static str) -> Self {
+        Self {
+            name,
+            type_name,
+            is_secret: false,
+        }
+    }
+
+    pub const fn secret(name: &'static str, type_name: &'static str) -> Self {
+        Self {
+            name,
+            type_name,
+            is_secret: true,
+        }
+    }
+}
+
+pub trait ConfigSchema: Default + Serialize {
+    const PREFIX: &'static str;
+    const FIELDS: &'static [ConfigField];
+
+    fn field(name: &str) -> Option<&'static ConfigField> {
+        Self::FIELDS.iter().find(|field| field.name == name)
+    }
+
+    fn schema() -> Vec<ConfigKey> {
+        let Value::Object(defaults) = serde_json::to_value(Self::default()).unwrap_or_default() else {
+            return Vec::new();
+        };
+
+        defaults
+            .into_iter()
+            .map(|(name, default)| {
+                let field = Self::field(&name);
+
+                ConfigKey {
+                    env_var: format!("{}{}", Self::PREFIX, name.to_uppercase()),
+                    type_name: field.map_or_else(|| inferred_type_name(&default), |field| field.type_name),
+                    default,
+                    is_secret: field.is_none_or(|field| field.is_secret),
+                }
+            })
+            .collect()
+    }
+
+    fn redacted(&self) -> Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+
+        if let Value::Object(map) = &mut value {
+            for (name, field_value) in map.iter_mut() {
+                if Self::field(name).is_none_or(|field| field.is_secret) && !is_blank(field_value) {
+                    *field_value = Value::String("********".to_owned());
+                }
+            }
+        }
+
+        value
+    }
+}
+
+fn inferred_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "unknown?",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "list",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(string) => string.is_empty(),
+        Value::Array(array) => array.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct TestConfig {
+        name: String,
+        password: String,
+        api_key: String,
+        empty_secret: Option<String>,
+    }
+
+    impl Default for TestConfig {
+        fn default() -> Self {
+            Self {
+                name: "app".to_owned(),
+                password: "hunter2".to_owned(),
+                api_key: "undeclared".to_owned(),
+                empty_secret: None,
+            }
+        }
+    }
+
+    impl ConfigSchema for TestConfig {
+        const PREFIX: &'static str = "TEST_";
+        const FIELDS: &'static [ConfigField] = &[
+            ConfigField::new("name", "string"),
+            ConfigField::secret("password", "string"),
+        ];
+    }
+
+    #[test]
+    fn schema_includes_undeclared_fields_as_secret() {
+        let schema = TestConfig::schema();
+        let api_key = schema.iter().find(|key| key.env_var == "TEST_API_KEY").unwrap();
+        let name = schema.iter().find(|key| key.env_var == "TEST_NAME").unwrap();
+
+        assert_eq!(schema.len(), 4);
+        assert!(api_key.is_secret);
+        assert_eq!(api_key.type_name, "string");
+        assert!(!name.is_secret);
+        assert_eq!(name.default, Value::String("app".to_owned()));
+    }
+
+    #[test]
+    fn redacted_hides_secret_and_undeclared_fields() {
+        let redacted = TestConfig::default().redacted();
+
+        assert_eq!(redacted["name"], "app");
+        assert_eq!(redacted["password"], "********");
+        assert_eq!(redacted["api_key"], "********");
+        assert_eq!(redacted["empty_secret"], Value::Null);
+    }
+
+    #[test]
+    fn config_schema_lists_app_token_as_secret() {
+        assert!(
+            config_schema()
+                .iter()
+                .any(|key| key.env_var == "APP_TOKEN" && key.is_secret)
+        );
+    }
+}
@@ -10,9 +10,22 @@ use url::Url;
 use uuid::Uuid;
 
 use super::config::IDENTITY_CLIENT_CONFIG;
+#[cfg(feature = "metrics")]
+use crate::metrics::{Counter, Histogram};
 
 static IDENTITY_CLIENT: OnceLock<IdentityClient> = OnceLock::new();
 
+#[cfg(feature = "metrics")]
+const IDENTITY_CLIENT_CALLS: Counter = Counter::new(
+    "identity_client_calls_total",
+    "Total number of identity provider calls.",
+);
+#[cfg(feature = "metrics")]
+const IDENTITY_CLIENT_CALL_DURATION: Histogram = Histogram::new(
+    "identity_client_call_duration_seconds",
+    "Identity provider call latency in seconds.",
+);
+
 #[derive(Clone)]
 pub struct IdentityClient<'a> {
     id: Uuid,
@@ -45,6 +58,23 @@ impl<'a> IdentityClient<'a> {
         })
     }
 
+    pub async fn ping(&self) -> anyhow::Result<()> {
+        let status = observe_call("ping", async {
+            Ok(reqwest::Client::new()
+                .get(self.provider_api_url.clone())
+                .send()
+                .await?
+                .status())
+        })
+        .await?;
+
+        if status.is_server_error() {
+            return Err(anyhow::anyhow!("Identity provider responded with {status}"));
+        }
+
+        Ok(())
+    }
+
     pub async fn refresh_auth(&self, auth: &Auth<'_>) -> anyhow::Result<Auth<'_>> {
         if auth.is_expired() {
             return Err(anyhow::anyhow!("Authorization is expired"));
@@ -52,13 +82,16 @@ impl<'a> IdentityClient<'a> {
 
         let url = self.provider_api_url.join("auth/refresh")?;
 
-        Ok(reqwest::Client::new()
-            .put(url)
-            .json(&self.auth_body(auth))
-            .send()
-            .await?
-            .json()
-            .await?)
+        observe_call("refresh_auth", async {
+            Ok(reqwest::Client::new()
+                .put(url)
+                .json(&self.auth_body(auth))
+                .send()
+                .await?
+                .json()
+                .await?)
+        })
+        .await
     }
 
     pub async fn revoke_auth(&self, auth: &Auth<'_>) -> anyhow::Result<()> {
@@ -68,13 +101,16 @@ impl<'a> IdentityClient<'a> {
 
         let url = self.provider_api_url.join("auth/revoke")?;
 
-        Ok(reqwest::Client::new()
-            .delete(url)
-            .json(&self.auth_body(auth))
-            .send()
-            .await?
-            .json()
-            .await?)
+        observe_call("revoke_auth", async {
+            Ok(reqwest::Client::new()
+                .delete(url)
+                .json(&self.auth_body(auth))
+                .send()
+                .await?
+                .json()
+                .await?)
+        })
+        .await
     }
 
     pub async fn verify_auth(&self, auth: &Auth<'_>) -> anyhow::Result<bool> {
@@ -84,13 +120,16 @@ impl<'a> IdentityClient<'a> {
 
         let url = self.provider_api_url.join("auth/verify")?;
 
-        Ok(reqwest::Client::new()
-            .get(url)
-            .json(&self.auth_body(auth))
-            .send()
-            .await?
-            .status()
-            .is_success())
+        observe_call("verify_auth", async {
+            Ok(reqwest::Client::new()
+                .get(url)
+                .json(&self.auth_body(auth))
+                .send()
+                .await?
+                .status()
+                .is_success())
+        })
+        .await
     }
 
     pub async fn user_info(&self, auth: &Auth<'_>) -> anyhow::Result<UserInfo<'_>> {
@@ -100,13 +139,16 @@ impl<'a> IdentityClient<'a> {
 
         let url = self.provider_api_url.join("user-info")?;
 
-        Ok(reqwest::Client::new()
-            .get(url)
-            .bearer_auth(auth.token.clone())
-            .send()
-            .await?
-            .json()
-            .await?)
+        observe_call("user_info", async {
+            Ok(reqwest::Client::new()
+                .get(url)
+                .bearer_auth(auth.token.clone())
+                .send()
+                .await?
+                .json()
+                .await?)
+        })
+        .await
     }
 
     pub fn webhook_event(&self, signature: &[u8], body: &[u8]) -> anyhow::Result<WebhookEvent> {
@@ -125,6 +167,26 @@ impl<'a> IdentityClient<'a> {
     }
 }
 
+async fn observe_call<T>(operation: &'static str, call: impl Future<Output = anyhow::Result<T>>) -> anyhow::Result<T> {
+    #[cfg(feature = "metrics")]
+    let started_at = std::time::Instant::now();
+
+    let result = call.await;
+
+    #[cfg(feature = "metrics")]
+    {
+        let outcome = if result.is_ok() { "success" } else { "error" };
+
+        IDENTITY_CLIENT_CALLS.increment(&[("operation", operation), ("outcome", outcome)]);
+        IDENTITY_CLIENT_CALL_DURATION.observe_duration(&[("operation", operation)], started_at.elapsed());
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    let _ = operation;
+
+    result
+}
+
 #[derive(Clone, Deserialize, Serialize)]
 pub struct Auth<'a> {
     pub token: Cow<'a, str>,
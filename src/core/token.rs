@@ -0,0 +1,167 @@
+use rand::{CryptoRng, Rng, rng};
+use sha2::{Digest, Sha256};
+
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const HEX: &[u8] = b"0123456789abcdef";
+const NUMERIC: &[u8] = b"0123456789";
+const URL_SAFE_BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn secure_rng() -> impl CryptoRng + Rng {
+    // `ThreadRng` is a ChaCha-based CSPRNG periodically reseeded from the OS.
+    rng()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TokenCharset {
+    #[default]
+    Alphanumeric,
+    Hex,
+    Numeric,
+    UrlSafeBase64,
+    Custom(&'static str),
+}
+
+impl TokenCharset {
+    fn chars(&self) -> Vec<char> {
+        match self {
+            Self::Alphanumeric => ALPHANUMERIC.iter().map(|&byte| byte as char).collect(),
+            Self::Hex => HEX.iter().map(|&byte| byte as char).collect(),
+            Self::Numeric => NUMERIC.iter().map(|&byte| byte as char).collect(),
+            Self::UrlSafeBase64 => URL_SAFE_BASE64.iter().map(|&byte| byte as char).collect(),
+            Self::Custom(chars) => chars.chars().collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RandomToken {
+    charset: TokenCharset,
+    length: usize,
+}
+
+impl Default for RandomToken {
+    fn default() -> Self {
+        Self {
+            charset: TokenCharset::Alphanumeric,
+            length: 32,
+        }
+    }
+}
+
+impl RandomToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn alphanumeric(length: usize) -> Self {
+        Self::new().length(length)
+    }
+
+    pub fn hex(length: usize) -> Self {
+        Self::new().charset(TokenCharset::Hex).length(length)
+    }
+
+    pub fn otp(length: usize) -> Self {
+        Self::new().charset(TokenCharset::Numeric).length(length)
+    }
+
+    pub fn url_safe(length: usize) -> Self {
+        Self::new().charset(TokenCharset::UrlSafeBase64).length(length)
+    }
+
+    #[track_caller]
+    pub fn charset(mut self, charset: TokenCharset) -> Self {
+        assert!(
+            !matches!(charset, TokenCharset::Custom(chars) if chars.is_empty()),
+            "Token charset must not be empty"
+        );
+
+        self.charset = charset;
+        self
+    }
+
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+    pub fn generate(&self) -> String {
+        let chars = self.charset.chars();
+        let mut rng = secure_rng();
+
+        (0..self.length)
+            .map(|_| chars[rng.random_range(0..chars.len())])
+            .collect()
+    }
+}
+
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub fn verify_token_hash(token: &str, hash: &str) -> bool {
+    constant_time_eq(hash_token(token).as_bytes(), hash.to_lowercase().as_bytes())
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_have_the_requested_length_and_charset() {
+        for (charset, chars) in [
+            (TokenCharset::Alphanumeric, ALPHANUMERIC),
+            (TokenCharset::Hex, HEX),
+            (TokenCharset::Numeric, NUMERIC),
+            (TokenCharset::UrlSafeBase64, URL_SAFE_BASE64),
+            (TokenCharset::Custom("xyz"), b"xyz".as_slice()),
+        ] {
+            for length in [0, 1, 32, 256, 1000] {
+                let token = RandomToken::new().charset(charset).length(length).generate();
+
+                assert_eq!(token.chars().count(), length);
+                assert!(
+                    token.bytes().all(|byte| chars.contains(&byte)),
+                    "{token} is not in {charset:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Token charset must not be empty")]
+    fn empty_custom_charsets_are_rejected_when_set() {
+        let _ = RandomToken::new().charset(TokenCharset::Custom(""));
+    }
+
+    #[test]
+    fn token_hashes_are_sha256_hex() {
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        assert_eq!(hash_token("abc"), hash);
+        assert!(verify_token_hash("abc", hash));
+        assert!(verify_token_hash("abc", &hash.to_uppercase()));
+        assert!(!verify_token_hash("abd", hash));
+        assert!(!verify_token_hash("abc", &hash[1..]));
+    }
+
+    #[test]
+    fn constant_time_eq_compares_lengths_and_bytes() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+        assert!(!constant_time_eq(b"", b"t"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
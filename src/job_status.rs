@@ -0,0 +1,103 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(feature = "monitor", feature = "server"))]
+use std::sync::{Arc, OnceLock};
+
+#[cfg(feature = "server")]
+use std::pin::Pin;
+
+#[cfg(any(feature = "monitor", feature = "server"))]
+static JOB_STATUS_QUERY: OnceLock<Arc<dyn JobStatusQuery>> = OnceLock::new();
+
+#[cfg(feature = "server")]
+static JOB_STATUS_AUTHORIZER: OnceLock<Arc<dyn JobStatusAuthorizer>> = OnceLock::new();
+
+#[cfg(feature = "server")]
+type AuthorizeFuture = Pin<Box<dyn Future<Output = bool> + Send>>;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed)
+    }
+}
+
+impl Display for JobState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Queued => "Queued",
+            Self::Running => "Running",
+            Self::Succeeded => "Succeeded",
+            Self::Failed => "Failed",
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JobStatusInfo {
+    pub task_id: String,
+    pub job_name: String,
+    pub state: JobState,
+    pub attempts: usize,
+    pub error: Option<String>,
+}
+
+#[cfg(any(feature = "monitor", feature = "server"))]
+pub trait JobStatusQuery: Send + Sync + 'static {
+    fn job_status(&self, task_id: &str) -> Option<JobStatusInfo>;
+}
+
+#[cfg(any(feature = "monitor", feature = "server"))]
+pub fn set_job_status_query(query: impl JobStatusQuery) -> bool {
+    JOB_STATUS_QUERY.set(Arc::new(query)).is_ok()
+}
+
+#[cfg(feature = "monitor")]
+pub fn job_status_query() -> Option<Arc<dyn JobStatusQuery>> {
+    Some(
+        JOB_STATUS_QUERY
+            .get_or_init(|| crate::monitor::job_status_tracker())
+            .clone(),
+    )
+}
+
+#[cfg(all(feature = "server", not(feature = "monitor")))]
+pub fn job_status_query() -> Option<Arc<dyn JobStatusQuery>> {
+    JOB_STATUS_QUERY.get().cloned()
+}
+
+#[cfg(feature = "server")]
+pub trait JobStatusAuthorizer: Send + Sync + 'static {
+    fn authorize(&self, headers: http::HeaderMap, status: JobStatusInfo) -> AuthorizeFuture;
+}
+
+#[cfg(feature = "server")]
+impl<F, Fut> JobStatusAuthorizer for F
+where
+    F: Fn(http::HeaderMap, JobStatusInfo) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = bool> + Send + 'static,
+{
+    fn authorize(&self, headers: http::HeaderMap, status: JobStatusInfo) -> AuthorizeFuture {
+        Box::pin(self(headers, status))
+    }
+}
+
+#[cfg(feature = "server")]
+pub fn set_job_status_authorizer(authorizer: impl JobStatusAuthorizer) -> bool {
+    JOB_STATUS_AUTHORIZER.set(Arc::new(authorizer)).is_ok()
+}
+
+#[cfg(feature = "server")]
+pub fn job_status_authorizer() -> Option<Arc<dyn JobStatusAuthorizer>> {
+    JOB_STATUS_AUTHORIZER.get().cloned()
+}
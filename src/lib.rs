@@ -1,9 +1,21 @@
 #[cfg(feature = "app")]
 pub mod app;
+#[cfg(feature = "build")]
+pub mod build;
 #[cfg(feature = "core")]
 pub mod core;
+#[cfg(any(feature = "app", feature = "monitor"))]
+pub mod job_status;
+#[cfg(feature = "mail")]
+pub mod mail;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 #[cfg(feature = "monitor")]
 pub mod monitor;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
 
 pub mod constants;
 
@@ -11,24 +23,20 @@ pub mod constants;
 pub mod test_utils;
 
 #[cfg(feature = "build")]
-pub fn setup_build_env() {
-    println!("cargo:rerun-if-env-changed=APP_SERVER_URL");
-    println!("cargo:rerun-if-env-changed=APP_TITLE");
-    println!("cargo:rerun-if-env-changed=APP_TOKEN");
-    println!("cargo:rerun-if-env-changed=IDENTITY_CLIENT_ID");
-    println!("cargo:rerun-if-env-changed=IDENTITY_CLIENT_PROVIDER_APP_URL");
+pub use build::setup_build_env;
 
-    let app_server_url = std::env::var("APP_SERVER_URL").unwrap_or_default();
-    let app_title = std::env::var("APP_TITLE").unwrap_or("Mango³".to_owned());
-    let app_token = std::env::var("APP_TOKEN").unwrap_or_default();
-    let identity_client_id = std::env::var("IDENTITY_CLIENT_ID").unwrap_or_default();
-    let identity_client_provider_app_url = std::env::var("IDENTITY_CLIENT_PROVIDER_APP_URL").unwrap_or_default();
+#[macro_export]
+macro_rules! include_client_config {
+    () => {
+        include!(concat!(env!("OUT_DIR"), "/client_config.rs"));
+    };
+}
 
-    println!("cargo:rustc-env=APP_SERVER_URL={app_server_url}");
-    println!("cargo:rustc-env=APP_TITLE={app_title}");
-    println!("cargo:rustc-env=APP_TOKEN={app_token}");
-    println!("cargo:rustc-env=IDENTITY_CLIENT_ID={identity_client_id}");
-    println!("cargo:rustc-env=IDENTITY_CLIENT_PROVIDER_APP_URL={identity_client_provider_app_url}");
+#[macro_export]
+macro_rules! include_build_info {
+    () => {
+        include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+    };
 }
 
 pub trait AsyncInto<T> {
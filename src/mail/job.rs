@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use apalis::prelude::Error;
+
+use crate::monitor::{Job, JobOptions, JobResultExt};
+
+use super::{MailMessage, send_mail};
+
+impl Job for MailMessage {
+    const NAME: &'static str = "mail";
+
+    fn options() -> JobOptions {
+        JobOptions::new()
+            .retries(5)
+            .backoff(Duration::from_secs(30), Duration::from_secs(3600))
+    }
+}
+
+pub async fn send_mail_job(message: MailMessage) -> Result<(), Error> {
+    send_mail(&message).await.classified()
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use std::sync::{Arc, LazyLock};
+
+    use crate::mail::{MemoryMailer, set_mailer};
+    use crate::test_utils::{JobHarness, TestBackend, WorkerEvent};
+
+    use super::*;
+
+    static MAILER: LazyLock<Arc<MemoryMailer>> = LazyLock::new(|| {
+        let mailer = Arc::new(MemoryMailer::new("sender@example.com"));
+
+        set_mailer(mailer.clone());
+
+        mailer
+    });
+
+    #[test]
+    fn mail_jobs_are_sent_and_invalid_addresses_are_not_retried() {
+        let backend = TestBackend::new();
+        let sent = backend.enqueue(MailMessage::new("user@example.com", "Welcome", "Hello"));
+        let invalid = backend.enqueue(MailMessage::new("not an address", "Welcome", "Hello"));
+
+        LazyLock::force(&MAILER);
+
+        let run = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(JobHarness::new().register_job(&backend, send_mail_job).run_until_idle());
+
+        assert!(backend.outcome(&sent).unwrap().is_success());
+        assert_eq!(backend.outcome(&invalid).unwrap().attempts, 1);
+        assert!(backend.retry_delays().is_empty());
+        assert_eq!(MAILER.messages_to("user@example.com").len(), 1);
+        assert!(run.has_event(MailMessage::NAME, &WorkerEvent::Engage(sent.to_string())));
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt::{self, Display};
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+
+use lettre::Message;
+use lettre::message::{Mailbox, MultiPart, SinglePart};
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::{MAIL_CONFIG, MailConfig};
+
+#[cfg(feature = "monitor")]
+mod job;
+mod template;
+mod transport;
+
+#[cfg(feature = "monitor")]
+pub use job::*;
+pub use template::*;
+pub use transport::*;
+
+static MAILER: OnceLock<Arc<dyn Mailer>> = OnceLock::new();
+
+pub type MailFuture<'a> = Pin<Box<dyn Future<Output = Result<(), MailError>> + Send + 'a>>;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransport {
+    Smtp,
+    File,
+    Memory,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    None,
+    Starttls,
+    Tls,
+}
+
+#[derive(Debug)]
+pub enum MailError {
+    Address(lettre::address::AddressError),
+    Message(lettre::error::Error),
+    Template(String),
+    Smtp(lettre::transport::smtp::Error),
+    Io(std::io::Error),
+    NotConfigured,
+}
+
+impl Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address(error) => write!(f, "Invalid mail address: {error}"),
+            Self::Message(error) => write!(f, "Could not build mail message: {error}"),
+            Self::Template(message) => write!(f, "Could not render mail template: {message}"),
+            Self::Smtp(error) => write!(f, "Could not send mail via SMTP: {error}"),
+            Self::Io(error) => write!(f, "Could not write mail: {error}"),
+            Self::NotConfigured => f.write_str("No mailer has been set up"),
+        }
+    }
+}
+
+impl std::error::Error for MailError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Address(error) => Some(error),
+            Self::Message(error) => Some(error),
+            Self::Template(_) | Self::NotConfigured => None,
+            Self::Smtp(error) => Some(error),
+            Self::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<lettre::address::AddressError> for MailError {
+    fn from(error: lettre::address::AddressError) -> Self {
+        Self::Address(error)
+    }
+}
+
+impl From<lettre::error::Error> for MailError {
+    fn from(error: lettre::error::Error) -> Self {
+        Self::Message(error)
+    }
+}
+
+impl From<lettre::transport::smtp::Error> for MailError {
+    fn from(error: lettre::transport::smtp::Error) -> Self {
+        Self::Smtp(error)
+    }
+}
+
+impl From<std::io::Error> for MailError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MailMessage {
+    pub to: String,
+    pub sender: Option<String>,
+    pub reply_to: Option<String>,
+    pub subject: String,
+    pub text: String,
+    pub html: Option<String>,
+}
+
+impl MailMessage {
+    pub fn new(to: impl Into<String>, subject: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            to: to.into(),
+            sender: None,
+            reply_to: None,
+            subject: subject.into(),
+            text: text.into(),
+            html: None,
+        }
+    }
+
+    pub fn sender(mut self, sender: impl Into<String>) -> Self {
+        self.sender = Some(sender.into());
+        self
+    }
+
+    pub fn reply_to(mut self, reply_to: impl Into<String>) -> Self {
+        self.reply_to = Some(reply_to.into());
+        self
+    }
+
+    pub fn html(mut self, html: impl Into<String>) -> Self {
+        self.html = Some(html.into());
+        self
+    }
+
+    pub fn to_message(&self, default_sender: &str) -> Result<Message, MailError> {
+        let sender = self.sender.as_deref().unwrap_or(default_sender);
+        let mut builder = Message::builder()
+            .from(sender.parse::<Mailbox>()?)
+            .to(self.to.parse::<Mailbox>()?)
+            .subject(&self.subject);
+
+        if let Some(reply_to) = &self.reply_to {
+            builder = builder.reply_to(reply_to.parse::<Mailbox>()?);
+        }
+
+        let message = match &self.html {
+            Some(html) => builder.multipart(MultiPart::alternative_plain_html(self.text.clone(), html.clone()))?,
+            None => builder.singlepart(SinglePart::plain(self.text.clone()))?,
+        };
+
+        Ok(message)
+    }
+}
+
+pub trait Mailer: Send + Sync + 'static {
+    fn send<'a>(&'a self, message: &MailMessage) -> MailFuture<'a>;
+}
+
+impl<M: Mailer> Mailer for Arc<M> {
+    fn send<'a>(&'a self, message: &MailMessage) -> MailFuture<'a> {
+        self.as_ref().send(message)
+    }
+}
+
+pub fn set_mailer(mailer: impl Mailer) -> bool {
+    MAILER.set(Arc::new(mailer)).is_ok()
+}
+
+pub fn setup_mailer() -> Result<bool, MailError> {
+    setup_mailer_with(&MAIL_CONFIG)
+}
+
+pub fn setup_mailer_with(config: &MailConfig) -> Result<bool, MailError> {
+    Ok(MAILER.set(default_mailer(config)?).is_ok())
+}
+
+pub fn mailer() -> Option<Arc<dyn Mailer>> {
+    MAILER.get().cloned()
+}
+
+pub async fn send_mail(message: &MailMessage) -> Result<(), MailError> {
+    mailer().ok_or(MailError::NotConfigured)?.send(message).await
+}
+
+fn default_mailer(config: &MailConfig) -> Result<Arc<dyn Mailer>, MailError> {
+    config.sender.parse::<Mailbox>()?;
+
+    Ok(match config.transport {
+        MailTransport::Smtp => Arc::new(SmtpMailer::new(config)?),
+        MailTransport::File => Arc::new(FileMailer::new(&config.file_path, &config.sender)),
+        MailTransport::Memory => Arc::new(MemoryMailer::new(&config.sender)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_mailer_validates_config() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let _runtime = runtime.enter();
+        let config = MailConfig::default();
+
+        assert_eq!(config.transport, MailTransport::Smtp);
+        assert!(default_mailer(&config).is_ok());
+
+        let config = MailConfig {
+            sender: "not a mailbox".to_owned(),
+            ..MailConfig::default()
+        };
+
+        assert!(matches!(default_mailer(&config), Err(MailError::Address(_))));
+    }
+}
@@ -0,0 +1,113 @@
+use std::borrow::Cow;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{MailError, MailMessage};
+
+#[derive(Clone, Debug)]
+pub struct MailTemplate {
+    subject: Cow<'static, str>,
+    text: Cow<'static, str>,
+    html: Option<Cow<'static, str>>,
+}
+
+impl MailTemplate {
+    pub const fn new(subject: &'static str, text: &'static str, html: Option<&'static str>) -> Self {
+        Self {
+            subject: Cow::Borrowed(subject),
+            text: Cow::Borrowed(text),
+            html: match html {
+                Some(html) => Some(Cow::Borrowed(html)),
+                None => None,
+            },
+        }
+    }
+
+    pub fn from_strings(subject: String, text: String, html: Option<String>) -> Self {
+        Self {
+            subject: Cow::Owned(subject),
+            text: Cow::Owned(text),
+            html: html.map(Cow::Owned),
+        }
+    }
+
+    pub fn render(&self, to: impl Into<String>, context: &impl Serialize) -> Result<MailMessage, MailError> {
+        let context = serde_json::to_value(context).map_err(|err| MailError::Template(err.to_string()))?;
+        let mut message = MailMessage::new(
+            to,
+            render_template(&self.subject, &context, false)?,
+            render_template(&self.text, &context, false)?,
+        );
+
+        if let Some(html) = &self.html {
+            message = message.html(render_template(html, &context, true)?);
+        }
+
+        Ok(message)
+    }
+}
+
+pub fn render_template(template: &str, context: &Value, escape: bool) -> Result<String, MailError> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+
+        let after_start = &rest[start + 2..];
+        let Some(end) = after_start.find("}}") else {
+            return Err(MailError::Template("Unclosed template variable".to_owned()));
+        };
+
+        let name = after_start[..end].trim();
+        let value =
+            lookup(context, name).ok_or_else(|| MailError::Template(format!("Missing template variable: {name}")))?;
+        let value = match value {
+            Value::Null => String::new(),
+            Value::String(string) => string.clone(),
+            value => value.to_string(),
+        };
+
+        if escape {
+            output.push_str(&escape_html(&value));
+        } else {
+            output.push_str(&value);
+        }
+
+        rest = &after_start[end + 2..];
+    }
+
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+fn lookup<'a>(context: &'a Value, name: &str) -> Option<&'a Value> {
+    if name.is_empty() {
+        return None;
+    }
+
+    name.split('.').try_fold(context, |value, key| match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(array) => key.parse::<usize>().ok().and_then(|index| array.get(index)),
+        _ => None,
+    })
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for char in value.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            char => escaped.push(char),
+        }
+    }
+
+    escaped
+}
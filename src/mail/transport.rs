@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use uuid::Uuid;
+
+use crate::core::config::MailConfig;
+
+use super::{MailError, MailFuture, MailMessage, Mailer, SmtpTls};
+
+pub struct SmtpMailer {
+    sender: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &MailConfig) -> Result<Self, MailError> {
+        let builder = match config.smtp_tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host),
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?,
+        };
+
+        let mut builder = builder.port(config.smtp_port);
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            sender: config.sender.clone(),
+            transport: builder.build(),
+        })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    fn send<'a>(&'a self, message: &MailMessage) -> MailFuture<'a> {
+        let message = message.to_message(&self.sender);
+
+        Box::pin(async move {
+            self.transport.send(message?).await?;
+
+            Ok(())
+        })
+    }
+}
+
+pub struct FileMailer {
+    path: PathBuf,
+    sender: String,
+}
+
+impl FileMailer {
+    pub fn new(path: impl Into<PathBuf>, sender: &str) -> Self {
+        Self {
+            path: path.into(),
+            sender: sender.to_owned(),
+        }
+    }
+}
+
+impl Mailer for FileMailer {
+    fn send<'a>(&'a self, message: &MailMessage) -> MailFuture<'a> {
+        let message = message.to_message(&self.sender);
+
+        Box::pin(async move {
+            let contents = message?.formatted();
+            let directory = self.path.clone();
+            let file_path = self
+                .path
+                .join(format!("{}-{}.eml", Utc::now().format("%Y%m%d%H%M%S"), Uuid::new_v4()));
+
+            tokio::task::spawn_blocking({
+                let file_path = file_path.clone();
+
+                move || {
+                    std::fs::create_dir_all(&directory)?;
+                    std::fs::write(&file_path, contents)
+                }
+            })
+            .await
+            .map_err(std::io::Error::other)??;
+
+            tracing::info!("Mail written to {}", file_path.display());
+
+            Ok(())
+        })
+    }
+}
+
+pub struct MemoryMailer {
+    sender: String,
+    messages: Mutex<Vec<MailMessage>>,
+}
+
+impl MemoryMailer {
+    pub fn new(sender: &str) -> Self {
+        Self {
+            sender: sender.to_owned(),
+            messages: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn messages(&self) -> Vec<MailMessage> {
+        self.messages
+            .lock()
+            .map(|messages| messages.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn messages_to(&self, to: &str) -> Vec<MailMessage> {
+        self.messages().into_iter().filter(|message| message.to == to).collect()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut messages) = self.messages.lock() {
+            messages.clear();
+        }
+    }
+}
+
+impl Mailer for MemoryMailer {
+    fn send<'a>(&'a self, message: &MailMessage) -> MailFuture<'a> {
+        let result = message.to_message(&self.sender).map(|_| message.clone());
+
+        Box::pin(async move {
+            let message = result?;
+
+            tracing::debug!("Mail to {} kept in memory", message.to);
+
+            if let Ok(mut messages) = self.messages.lock() {
+                messages.push(message);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_mailer_writes_messages_into_a_new_directory() {
+        let path = std::env::temp_dir()
+            .join(format!("mail-{}", Uuid::new_v4()))
+            .join("outbox");
+        let mailer = FileMailer::new(&path, "sender@example.com");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime
+            .block_on(mailer.send(&MailMessage::new("user@example.com", "Welcome", "Hello there")))
+            .unwrap();
+
+        let files = std::fs::read_dir(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let contents = std::fs::read_to_string(files[0].path()).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(contents.contains("Subject: Welcome"));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub const DEFAULT_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static METRICS_REGISTRY: LazyLock<MetricsRegistry> = LazyLock::new(MetricsRegistry::default);
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Default)]
+pub struct MetricsRegistry {
+    families: Mutex<BTreeMap<&'static str, MetricFamily>>,
+}
+
+struct MetricFamily {
+    help: &'static str,
+    kind: MetricKind,
+}
+
+enum MetricKind {
+    Counter(BTreeMap<Labels, u64>),
+    Histogram(&'static [f64], BTreeMap<Labels, HistogramSeries>),
+}
+
+impl MetricKind {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Counter(_) => "counter",
+            Self::Histogram(..) => "histogram",
+        }
+    }
+}
+
+struct HistogramSeries {
+    bucket_counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl MetricsRegistry {
+    pub fn global() -> &'static Self {
+        &METRICS_REGISTRY
+    }
+
+    fn family<F>(&self, name: &'static str, help: &'static str, init: F, update: impl FnOnce(&mut MetricKind))
+    where
+        F: FnOnce() -> MetricKind,
+    {
+        let mut families = self.families.lock().expect("Could not lock metrics registry");
+        let kind = init();
+        let expected = std::mem::discriminant(&kind);
+        let family = families.entry(name).or_insert(MetricFamily { help, kind });
+
+        if std::mem::discriminant(&family.kind) != expected {
+            let registered = family.kind.type_name();
+
+            drop(families);
+            panic!("Metric {name} is already registered as a {registered}");
+        }
+
+        update(&mut family.kind);
+    }
+
+    pub fn render(&self) -> String {
+        let families = self.families.lock().expect("Could not lock metrics registry");
+        let mut output = String::new();
+
+        for (name, family) in families.iter() {
+            let _ = writeln!(output, "# HELP {name} {}", family.help);
+
+            match &family.kind {
+                MetricKind::Counter(series) => {
+                    let _ = writeln!(output, "# TYPE {name} counter");
+
+                    for (labels, value) in series {
+                        let _ = writeln!(output, "{name}{} {value}", format_labels(labels, None));
+                    }
+                }
+                MetricKind::Histogram(buckets, series) => {
+                    let _ = writeln!(output, "# TYPE {name} histogram");
+
+                    for (labels, histogram) in series {
+                        for (bucket, count) in buckets.iter().zip(&histogram.bucket_counts) {
+                            let _ = writeln!(
+                                output,
+                                "{name}_bucket{} {count}",
+                                format_labels(labels, Some(&bucket.to_string()))
+                            );
+                        }
+
+                        let _ = writeln!(
+                            output,
+                            "{name}_bucket{} {}",
+                            format_labels(labels, Some("+Inf")),
+                            histogram.count
+                        );
+                        let _ = writeln!(output, "{name}_sum{} {}", format_labels(labels, None), histogram.sum);
+                        let _ = writeln!(
+                            output,
+                            "{name}_count{} {}",
+                            format_labels(labels, None),
+                            histogram.count
+                        );
+                    }
+                }
+            }
+        }
+
+        output
+    }
+}
+
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+}
+
+impl Counter {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help }
+    }
+
+    pub fn increment(&self, labels: &[(&'static str, &str)]) {
+        self.increment_by(labels, 1);
+    }
+
+    pub fn increment_by(&self, labels: &[(&'static str, &str)], value: u64) {
+        self.set_with(labels, |current| current + value);
+    }
+
+    pub fn set(&self, labels: &[(&'static str, &str)], value: u64) {
+        self.set_with(labels, |_| value);
+    }
+
+    fn set_with(&self, labels: &[(&'static str, &str)], update: impl FnOnce(u64) -> u64) {
+        METRICS_REGISTRY.family(
+            self.name,
+            self.help,
+            || MetricKind::Counter(BTreeMap::new()),
+            |kind| {
+                if let MetricKind::Counter(series) = kind {
+                    let value = series.entry(owned_labels(labels)).or_default();
+
+                    *value = update(*value);
+                }
+            },
+        );
+    }
+}
+
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    buckets: &'static [f64],
+}
+
+impl Histogram {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            buckets: DEFAULT_BUCKETS,
+        }
+    }
+
+    pub const fn with_buckets(mut self, buckets: &'static [f64]) -> Self {
+        self.buckets = buckets;
+        self
+    }
+
+    pub fn observe(&self, labels: &[(&'static str, &str)], value: f64) {
+        METRICS_REGISTRY.family(
+            self.name,
+            self.help,
+            || MetricKind::Histogram(self.buckets, BTreeMap::new()),
+            |kind| {
+                if let MetricKind::Histogram(buckets, series) = kind {
+                    let histogram = series.entry(owned_labels(labels)).or_insert_with(|| HistogramSeries {
+                        bucket_counts: vec![0; buckets.len()],
+                        count: 0,
+                        sum: 0.0,
+                    });
+
+                    for (bucket, count) in buckets.iter().zip(histogram.bucket_counts.iter_mut()) {
+                        if value <= *bucket {
+                            *count += 1;
+                        }
+                    }
+
+                    histogram.count += 1;
+                    histogram.sum += value;
+                }
+            },
+        );
+    }
+
+    pub fn observe_duration(&self, labels: &[(&'static str, &str)], duration: Duration) {
+        self.observe(labels, duration.as_secs_f64());
+    }
+}
+
+pub fn render_metrics() -> String {
+    #[cfg(feature = "core")]
+    record_app_token_usage();
+
+    METRICS_REGISTRY.render()
+}
+
+#[cfg(feature = "core")]
+fn record_app_token_usage() {
+    const APP_TOKEN_OLD_REQUESTS: Counter = Counter::new(
+        "app_token_old_requests_total",
+        "Requests authenticated with a rotated app token.",
+    );
+
+    for usage in crate::core::app_token::old_app_token_usage() {
+        APP_TOKEN_OLD_REQUESTS.set(
+            &[
+                ("index", &usage.index.to_string()),
+                ("client", usage.client.as_deref().unwrap_or_default()),
+                ("version", usage.version.as_deref().unwrap_or_default()),
+            ],
+            usage.requests,
+        );
+    }
+}
+
+fn owned_labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels.iter().map(|(key, value)| (*key, (*value).to_owned())).collect()
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs = labels
+        .iter()
+        .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(value)))
+        .collect::<Vec<_>>();
+
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_set_replaces_the_value() {
+        const COUNTER: Counter = Counter::new("test_counter_set_total", "Test counter.");
+
+        COUNTER.set(&[], 5);
+        COUNTER.set(&[], 3);
+
+        assert!(render_metrics().contains("test_counter_set_total 3\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Metric test_conflicting_metric is already registered as a counter")]
+    fn registering_a_metric_with_another_kind_panics() {
+        Counter::new("test_conflicting_metric", "Test counter.").increment(&[]);
+        Histogram::new("test_conflicting_metric", "Test histogram.").observe(&[], 1.0);
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use apalis::prelude::{BoxDynError, Error, Storage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::MONITOR_CONFIG;
+
+use super::{Job, JobStorageExt};
+
+const DEFAULT_MEMORY_CAPACITY: usize = 1000;
+
+static DEAD_LETTER_STORE: OnceLock<Arc<dyn DeadLetterStore>> = OnceLock::new();
+
+#[cfg(feature = "metrics")]
+const DEAD_LETTERS: crate::metrics::Counter =
+    crate::metrics::Counter::new("apalis_dead_letters_total", "Total number of dead-lettered jobs.");
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DeadLetter {
+    pub task_id: String,
+    pub job_name: String,
+    pub payload: serde_json::Value,
+    pub errors: Vec<String>,
+    pub attempts: usize,
+    pub first_attempt_at: DateTime<Utc>,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    pub fn new<J: Job>(
+        task_id: String,
+        job: &J,
+        error: &Error,
+        attempts: usize,
+        first_attempt_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            task_id,
+            job_name: J::NAME.to_owned(),
+            payload: serde_json::to_value(job).unwrap_or_default(),
+            errors: error_chain(error),
+            attempts,
+            first_attempt_at,
+            failed_at: Utc::now(),
+        }
+    }
+
+    pub fn job<J: Job>(&self) -> Result<J, serde_json::Error> {
+        serde_json::from_value(self.payload.clone())
+    }
+}
+
+pub trait DeadLetterStore: Send + Sync + 'static {
+    fn push(&self, letter: DeadLetter) -> Result<(), BoxDynError>;
+
+    fn list(&self) -> Result<Vec<DeadLetter>, BoxDynError>;
+
+    fn remove(&self, task_id: &str) -> Result<Option<DeadLetter>, BoxDynError>;
+}
+
+pub struct MemoryDeadLetterStore {
+    capacity: usize,
+    letters: Mutex<VecDeque<DeadLetter>>,
+}
+
+impl Default for MemoryDeadLetterStore {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_MEMORY_CAPACITY)
+    }
+}
+
+impl MemoryDeadLetterStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            letters: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl DeadLetterStore for MemoryDeadLetterStore {
+    fn push(&self, letter: DeadLetter) -> Result<(), BoxDynError> {
+        let mut letters = self.letters.lock().map_err(|err| err.to_string())?;
+
+        while letters.len() >= self.capacity.max(1) {
+            letters.pop_front();
+        }
+
+        letters.push_back(letter);
+
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<DeadLetter>, BoxDynError> {
+        Ok(self
+            .letters
+            .lock()
+            .map_err(|err| err.to_string())?
+            .iter()
+            .cloned()
+            .collect())
+    }
+
+    fn remove(&self, task_id: &str) -> Result<Option<DeadLetter>, BoxDynError> {
+        let mut letters = self.letters.lock().map_err(|err| err.to_string())?;
+        let position = letters.iter().position(|letter| letter.task_id == task_id);
+
+        Ok(position.and_then(|position| letters.remove(position)))
+    }
+}
+
+pub struct FileDeadLetterStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileDeadLetterStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> Result<Vec<DeadLetter>, BoxDynError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = OpenOptions::new().read(true).open(&self.path)?;
+        let mut letters = Vec::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+
+            if !line.trim().is_empty() {
+                letters.push(serde_json::from_str(&line)?);
+            }
+        }
+
+        Ok(letters)
+    }
+}
+
+impl DeadLetterStore for FileDeadLetterStore {
+    fn push(&self, letter: DeadLetter) -> Result<(), BoxDynError> {
+        let _guard = self.lock.lock().map_err(|err| err.to_string())?;
+        let mut file = OpenOptions::new().append(true).create(true).open(&self.path)?;
+
+        writeln!(file, "{}", serde_json::to_string(&letter)?)?;
+
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<DeadLetter>, BoxDynError> {
+        let _guard = self.lock.lock().map_err(|err| err.to_string())?;
+
+        self.read()
+    }
+
+    fn remove(&self, task_id: &str) -> Result<Option<DeadLetter>, BoxDynError> {
+        let _guard = self.lock.lock().map_err(|err| err.to_string())?;
+        let (removed, remaining): (Vec<_>, Vec<_>) =
+            self.read()?.into_iter().partition(|letter| letter.task_id == task_id);
+
+        if removed.is_empty() {
+            return Ok(None);
+        }
+
+        let temp_path = self.path.with_extension(format!("{}.tmp", std::process::id()));
+
+        {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&temp_path)?;
+
+            for letter in remaining {
+                writeln!(file, "{}", serde_json::to_string(&letter)?)?;
+            }
+
+            file.sync_all()?;
+        }
+
+        std::fs::rename(&temp_path, &self.path)?;
+
+        Ok(removed.into_iter().next())
+    }
+}
+
+pub fn set_dead_letter_store(store: impl DeadLetterStore) -> bool {
+    DEAD_LETTER_STORE.set(Arc::new(store)).is_ok()
+}
+
+pub fn dead_letter_store() -> Arc<dyn DeadLetterStore> {
+    DEAD_LETTER_STORE
+        .get_or_init(|| match &MONITOR_CONFIG.dead_letter_path {
+            Some(path) => Arc::new(FileDeadLetterStore::new(path)),
+            None => Arc::new(MemoryDeadLetterStore::default()),
+        })
+        .clone()
+}
+
+pub(crate) async fn record_dead_letter(letter: DeadLetter) {
+    tracing::error!(
+        "Job [{}] with id: {} was dead-lettered after {} attempts",
+        letter.job_name,
+        letter.task_id,
+        letter.attempts
+    );
+
+    #[cfg(feature = "metrics")]
+    DEAD_LETTERS.increment(&[("job", &letter.job_name)]);
+
+    let store = dead_letter_store();
+
+    if let Err(err) = blocking(move || store.push(letter)).await {
+        tracing::error!("Could not store dead letter: {err}");
+    }
+}
+
+pub async fn requeue_dead_letter<J, S>(storage: &mut S, task_id: &str) -> Result<bool, BoxDynError>
+where
+    J: Job,
+    S: Storage<Job = J> + Send,
+    S::Context: Send,
+    S::Error: Into<BoxDynError>,
+{
+    let store = dead_letter_store();
+    let Some(letter) = blocking({
+        let store = store.clone();
+        let task_id = task_id.to_owned();
+
+        move || store.remove(&task_id)
+    })
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    let job = match letter.job::<J>() {
+        Ok(job) => job,
+        Err(err) => {
+            blocking(move || store.push(letter)).await?;
+
+            return Err(err.into());
+        }
+    };
+
+    if let Err(err) = storage.push_job(job).await {
+        blocking(move || store.push(letter)).await?;
+
+        return Err(err.into());
+    }
+
+    Ok(true)
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, BoxDynError> + Send + 'static,
+) -> Result<T, BoxDynError> {
+    tokio::task::spawn_blocking(f).await?
+}
+
+fn error_chain(error: &Error) -> Vec<String> {
+    let mut chain = vec![error.to_string()];
+    let mut source = std::error::Error::source(error);
+
+    while let Some(error) = source {
+        chain.push(error.to_string());
+        source = error.source();
+    }
+
+    chain.dedup();
+
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letter(task_id: &str) -> DeadLetter {
+        DeadLetter {
+            task_id: task_id.to_owned(),
+            job_name: "test".to_owned(),
+            payload: serde_json::Value::Null,
+            errors: vec!["Failed".to_owned()],
+            attempts: 1,
+            first_attempt_at: Utc::now(),
+            failed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn file_store_removes_letters_by_replacing_the_file() {
+        let dir = std::env::temp_dir().join(format!("sdk-dead-letters-{}", std::process::id()));
+
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let store = FileDeadLetterStore::new(dir.join("dead_letters.jsonl"));
+
+        store.push(letter("first")).unwrap();
+        store.push(letter("second")).unwrap();
+
+        assert_eq!(store.remove("first").unwrap().unwrap().task_id, "first");
+        assert!(store.remove("first").unwrap().is_none());
+        assert_eq!(
+            store
+                .list()
+                .unwrap()
+                .into_iter()
+                .map(|letter| letter.task_id)
+                .collect::<Vec<_>>(),
+            ["second"]
+        );
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,400 @@
+use std::fmt::{self, Display};
+use std::sync::Arc;
+use std::time::Duration;
+
+use apalis::prelude::{BoxDynError, Error};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JobErrorKind {
+    Retryable,
+    Permanent,
+    RetryAfter(Duration),
+}
+
+pub trait JobErrorClass {
+    fn job_error_kind(&self) -> JobErrorKind;
+}
+
+#[derive(Debug)]
+pub struct JobError {
+    kind: JobErrorKind,
+    source: BoxDynError,
+}
+
+impl JobError {
+    pub fn new(kind: JobErrorKind, source: impl Into<BoxDynError>) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+        }
+    }
+
+    pub fn retryable(source: impl Into<BoxDynError>) -> Self {
+        Self::new(JobErrorKind::Retryable, source)
+    }
+
+    pub fn permanent(source: impl Into<BoxDynError>) -> Self {
+        Self::new(JobErrorKind::Permanent, source)
+    }
+
+    pub fn retry_after(source: impl Into<BoxDynError>, duration: Duration) -> Self {
+        Self::new(JobErrorKind::RetryAfter(duration), source)
+    }
+
+    pub fn kind(&self) -> JobErrorKind {
+        self.kind
+    }
+
+    pub fn is_permanent(&self) -> bool {
+        self.kind == JobErrorKind::Permanent
+    }
+
+    pub fn retry_after_duration(&self) -> Option<Duration> {
+        match self.kind {
+            JobErrorKind::RetryAfter(duration) => Some(duration),
+            _ => None,
+        }
+    }
+
+    pub fn from_apalis_error(error: &Error) -> Option<&Self> {
+        match error {
+            Error::Failed(source) | Error::Abort(source) => source.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::error::Error for JobError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl From<JobError> for Error {
+    fn from(error: JobError) -> Self {
+        let is_permanent = error.is_permanent();
+        let source = Arc::new(Box::new(error) as BoxDynError);
+
+        if is_permanent {
+            Error::Abort(source)
+        } else {
+            Error::Failed(source)
+        }
+    }
+}
+
+impl<E> From<E> for JobError
+where
+    E: JobErrorClass + std::error::Error + Send + Sync + 'static,
+{
+    fn from(error: E) -> Self {
+        Self::new(error.job_error_kind(), error)
+    }
+}
+
+impl From<anyhow::Error> for JobError {
+    fn from(error: anyhow::Error) -> Self {
+        let kind = error
+            .chain()
+            .find_map(classify_error)
+            .unwrap_or(JobErrorKind::Retryable);
+
+        Self::new(kind, error)
+    }
+}
+
+impl JobErrorClass for validator::ValidationErrors {
+    fn job_error_kind(&self) -> JobErrorKind {
+        JobErrorKind::Permanent
+    }
+}
+
+#[cfg(any(feature = "identity-client", feature = "webhooks"))]
+impl JobErrorClass for reqwest::Error {
+    fn job_error_kind(&self) -> JobErrorKind {
+        match self.status() {
+            Some(status) if status.as_u16() == 408 || status.as_u16() == 429 => JobErrorKind::Retryable,
+            Some(status) if status.is_client_error() => JobErrorKind::Permanent,
+            None if self.is_builder() || self.is_decode() => JobErrorKind::Permanent,
+            _ => JobErrorKind::Retryable,
+        }
+    }
+}
+
+#[cfg(feature = "mail")]
+impl JobErrorClass for crate::mail::MailError {
+    fn job_error_kind(&self) -> JobErrorKind {
+        match self {
+            Self::Address(_) | Self::Message(_) | Self::Template(_) => JobErrorKind::Permanent,
+            Self::Smtp(error) if error.is_permanent() => JobErrorKind::Permanent,
+            Self::Smtp(_) | Self::Io(_) | Self::NotConfigured => JobErrorKind::Retryable,
+        }
+    }
+}
+
+#[cfg(feature = "webhooks")]
+impl JobErrorClass for crate::webhooks::WebhookUrlError {
+    fn job_error_kind(&self) -> JobErrorKind {
+        match self {
+            Self::Resolve(_) | Self::Client(_) => JobErrorKind::Retryable,
+            Self::Invalid(_) | Self::InsecureScheme(_) | Self::MissingHost | Self::BlockedHost(_) => {
+                JobErrorKind::Permanent
+            }
+        }
+    }
+}
+
+fn classify_error(error: &(dyn std::error::Error + 'static)) -> Option<JobErrorKind> {
+    if let Some(error) = error.downcast_ref::<JobError>() {
+        return Some(error.kind());
+    }
+
+    if let Some(error) = error.downcast_ref::<validator::ValidationErrors>() {
+        return Some(error.job_error_kind());
+    }
+
+    #[cfg(any(feature = "identity-client", feature = "webhooks"))]
+    if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+        return Some(error.job_error_kind());
+    }
+
+    #[cfg(feature = "mail")]
+    if let Some(error) = error.downcast_ref::<crate::mail::MailError>() {
+        return Some(error.job_error_kind());
+    }
+
+    #[cfg(feature = "webhooks")]
+    if let Some(error) = error.downcast_ref::<crate::webhooks::WebhookUrlError>() {
+        return Some(error.job_error_kind());
+    }
+
+    None
+}
+
+pub trait JobResultExt<T, E> {
+    fn retryable(self) -> Result<T, Error>;
+
+    fn permanent(self) -> Result<T, Error>;
+
+    fn retry_after(self, duration: Duration) -> Result<T, Error>;
+
+    fn classified(self) -> Result<T, Error>
+    where
+        E: Into<JobError>;
+}
+
+impl<T, E: Into<BoxDynError>> JobResultExt<T, E> for Result<T, E> {
+    fn retryable(self) -> Result<T, Error> {
+        self.map_err(|error| JobError::retryable(error).into())
+    }
+
+    fn permanent(self) -> Result<T, Error> {
+        self.map_err(|error| JobError::permanent(error).into())
+    }
+
+    fn retry_after(self, duration: Duration) -> Result<T, Error> {
+        self.map_err(|error| JobError::retry_after(error, duration).into())
+    }
+
+    fn classified(self) -> Result<T, Error>
+    where
+        E: Into<JobError>,
+    {
+        self.map_err(|error| Into::<JobError>::into(error).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind_of(error: impl Into<JobError>) -> JobErrorKind {
+        error.into().kind()
+    }
+
+    #[derive(Debug)]
+    struct Wrapped(JobError);
+
+    impl Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("wrapped")
+        }
+    }
+
+    impl std::error::Error for Wrapped {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn anyhow_errors_are_classified_by_their_chain() {
+        let error = anyhow::Error::from(JobError::permanent("gone"))
+            .context("loading user")
+            .context("sending mail");
+        assert_eq!(kind_of(error), JobErrorKind::Permanent);
+
+        let error = anyhow::Error::new(Wrapped(JobError::retry_after("busy", Duration::from_secs(5)))).context("outer");
+        assert_eq!(kind_of(error), JobErrorKind::RetryAfter(Duration::from_secs(5)));
+
+        let error = anyhow::Error::from(validator::ValidationErrors::new()).context("validating");
+        assert_eq!(kind_of(error), JobErrorKind::Permanent);
+
+        let error = anyhow::Error::from(std::io::Error::other("disk")).context("writing");
+        assert_eq!(kind_of(error), JobErrorKind::Retryable);
+
+        assert_eq!(kind_of(anyhow::anyhow!("unknown")), JobErrorKind::Retryable);
+    }
+
+    #[test]
+    fn permanent_job_errors_abort_and_others_fail() {
+        let error = Error::from(JobError::permanent("gone"));
+        assert!(matches!(error, Error::Abort(_)));
+        assert!(JobError::from_apalis_error(&error).unwrap().is_permanent());
+
+        for job_error in [
+            JobError::retryable("flaky"),
+            JobError::retry_after("busy", Duration::from_secs(1)),
+        ] {
+            let kind = job_error.kind();
+            let error = Error::from(job_error);
+
+            assert!(matches!(error, Error::Failed(_)));
+            assert_eq!(JobError::from_apalis_error(&error).unwrap().kind(), kind);
+        }
+
+        assert_eq!(
+            JobError::retry_after("busy", Duration::from_secs(3)).retry_after_duration(),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[cfg(any(feature = "identity-client", feature = "webhooks"))]
+    fn status_error(status: u16) -> reqwest::Error {
+        let response = http::Response::builder().status(status).body("").unwrap();
+
+        reqwest::Response::from(response).error_for_status().unwrap_err()
+    }
+
+    #[cfg(any(feature = "identity-client", feature = "webhooks"))]
+    #[test]
+    fn reqwest_errors_are_classified_by_status() {
+        assert_eq!(kind_of(status_error(408)), JobErrorKind::Retryable);
+        assert_eq!(kind_of(status_error(429)), JobErrorKind::Retryable);
+        assert_eq!(kind_of(status_error(500)), JobErrorKind::Retryable);
+        assert_eq!(kind_of(status_error(503)), JobErrorKind::Retryable);
+        assert_eq!(kind_of(status_error(400)), JobErrorKind::Permanent);
+        assert_eq!(kind_of(status_error(404)), JobErrorKind::Permanent);
+
+        let builder_error = reqwest::Client::new().get("not a url").build().unwrap_err();
+        assert_eq!(kind_of(builder_error), JobErrorKind::Permanent);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let decode_error = runtime.block_on(async {
+            let response = http::Response::builder().status(200).body("not json").unwrap();
+
+            reqwest::Response::from(response).json::<u32>().await.unwrap_err()
+        });
+        assert_eq!(kind_of(decode_error), JobErrorKind::Permanent);
+
+        let error = anyhow::Error::from(status_error(429)).context("calling api");
+        assert_eq!(kind_of(error), JobErrorKind::Retryable);
+    }
+
+    #[cfg(feature = "mail")]
+    #[test]
+    fn mail_errors_are_classified() {
+        use std::io::Write;
+        use std::net::TcpListener;
+
+        use lettre::{AsyncSmtpTransport, Tokio1Executor};
+
+        use crate::mail::MailError;
+
+        assert_eq!(
+            kind_of(MailError::Address("invalid".parse::<lettre::Address>().unwrap_err())),
+            JobErrorKind::Permanent
+        );
+        assert_eq!(
+            kind_of(MailError::Message(lettre::error::Error::MissingFrom)),
+            JobErrorKind::Permanent
+        );
+        assert_eq!(kind_of(MailError::Template("missing".into())), JobErrorKind::Permanent);
+        assert_eq!(
+            kind_of(MailError::Io(std::io::Error::other("disk"))),
+            JobErrorKind::Retryable
+        );
+        assert_eq!(kind_of(MailError::NotConfigured), JobErrorKind::Retryable);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+
+            std::thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                stream.write_all(b"554 5.7.1 Rejected\r\n").unwrap();
+            });
+
+            let transport: AsyncSmtpTransport<Tokio1Executor> =
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous("127.0.0.1")
+                    .port(port)
+                    .build();
+            let error = transport.test_connection().await.unwrap_err();
+            assert!(error.is_permanent());
+            assert_eq!(kind_of(MailError::Smtp(error)), JobErrorKind::Permanent);
+
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            drop(listener);
+
+            let transport: AsyncSmtpTransport<Tokio1Executor> =
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous("127.0.0.1")
+                    .port(port)
+                    .build();
+            let error = transport.test_connection().await.unwrap_err();
+            assert!(!error.is_permanent());
+            assert_eq!(kind_of(MailError::Smtp(error)), JobErrorKind::Retryable);
+        });
+    }
+
+    #[cfg(feature = "webhooks")]
+    #[test]
+    fn webhook_url_errors_are_classified() {
+        use crate::webhooks::WebhookUrlError;
+
+        for error in [
+            WebhookUrlError::Invalid(url::ParseError::EmptyHost),
+            WebhookUrlError::InsecureScheme("http".into()),
+            WebhookUrlError::MissingHost,
+            WebhookUrlError::BlockedHost("127.0.0.1".into()),
+        ] {
+            assert_eq!(kind_of(error), JobErrorKind::Permanent);
+        }
+
+        let client_error = reqwest::Client::new().get("not a url").build().unwrap_err();
+
+        for error in [
+            WebhookUrlError::Resolve(std::io::Error::other("dns")),
+            WebhookUrlError::Client(client_error),
+        ] {
+            assert_eq!(kind_of(error), JobErrorKind::Retryable);
+        }
+
+        let error = anyhow::Error::from(WebhookUrlError::MissingHost).context("delivering");
+        assert_eq!(kind_of(error), JobErrorKind::Permanent);
+    }
+}
@@ -0,0 +1,581 @@
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+use std::pin::{Pin, pin};
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+
+use apalis::prelude::{
+    Backend, BoxDynError, Error, Monitor, Ready, Request, ServiceFn, Storage, Worker, WorkerId, service_fn,
+};
+use apalis_core::request::Parts;
+use chrono::Utc;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tower::limit::{ConcurrencyLimit, ConcurrencyLimitLayer};
+use tower::{Layer, Service};
+use tracing::Instrument;
+
+use super::{
+    DeadLetter, JobError, JobRequestId, JobRequestIdLayer, JobRequestIdService, job_status_tracker, record_dead_letter,
+    track_queued,
+};
+
+pub trait Job: Clone + DeserializeOwned + Serialize + Send + Sync + Unpin + 'static {
+    const NAME: &'static str;
+
+    fn options() -> JobOptions {
+        JobOptions::default()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct JobOptions {
+    pub retries: usize,
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    pub timeout: Option<Duration>,
+    pub concurrency: usize,
+    pub dead_letters: bool,
+}
+
+impl Default for JobOptions {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            timeout: Some(Duration::from_secs(60)),
+            concurrency: 10,
+            dead_letters: true,
+        }
+    }
+}
+
+impl JobOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Duration, max_backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn dead_letters(mut self, enabled: bool) -> Self {
+        self.dead_letters = enabled;
+        self
+    }
+
+    pub fn backoff_for(&self, attempt: usize) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1).min(31) as u32);
+
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+#[derive(Debug)]
+pub struct JobTimeoutError(pub Duration);
+
+impl Display for JobTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Job timed out after {} ms", self.0.as_millis())
+    }
+}
+
+impl std::error::Error for JobTimeoutError {}
+
+pub type RetryFuture = Pin<Box<dyn Future<Output = Result<(), BoxDynError>> + Send>>;
+
+pub trait RetryBackend<J, Ctx>: Send + Sync + 'static {
+    fn retry(&self, request: Request<J, Ctx>, delay: Duration) -> RetryFuture;
+}
+
+impl<S> RetryBackend<S::Job, S::Context> for S
+where
+    S: Storage + Clone + Send + Sync + 'static,
+    S::Job: Send + 'static,
+    S::Context: Send + 'static,
+    S::Error: Into<BoxDynError>,
+{
+    fn retry(&self, request: Request<S::Job, S::Context>, delay: Duration) -> RetryFuture {
+        let mut storage = self.clone();
+
+        Box::pin(async move { storage.reschedule(request, delay).await.map_err(Into::into) })
+    }
+}
+
+pub struct JobLayer<J, Ctx> {
+    name: &'static str,
+    options: JobOptions,
+    retry_backend: Option<Arc<dyn RetryBackend<J, Ctx>>>,
+}
+
+impl<J, Ctx> Clone for JobLayer<J, Ctx> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            options: self.options.clone(),
+            retry_backend: self.retry_backend.clone(),
+        }
+    }
+}
+
+impl<J, Ctx> JobLayer<J, Ctx> {
+    pub fn new(name: &'static str, options: JobOptions) -> Self {
+        Self {
+            name,
+            options,
+            retry_backend: None,
+        }
+    }
+
+    pub fn retry_backend(mut self, backend: impl RetryBackend<J, Ctx>) -> Self {
+        self.retry_backend = Some(Arc::new(backend));
+        self
+    }
+}
+
+impl<S, J, Ctx> Layer<S> for JobLayer<J, Ctx> {
+    type Service = JobService<S, J, Ctx>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        JobService {
+            inner,
+            layer: self.clone(),
+            _request: PhantomData,
+        }
+    }
+}
+
+pub struct JobService<S, J, Ctx> {
+    inner: S,
+    layer: JobLayer<J, Ctx>,
+    _request: PhantomData<fn(J, Ctx)>,
+}
+
+impl<S: Clone, J, Ctx> Clone for JobService<S, J, Ctx> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            layer: self.layer.clone(),
+            _request: PhantomData,
+        }
+    }
+}
+
+impl<S, J, Ctx> Service<Request<J, Ctx>> for JobService<S, J, Ctx>
+where
+    S: Service<Request<J, Ctx>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    J: Job,
+    Ctx: Clone + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<J, Ctx>) -> Self::Future {
+        let JobLayer {
+            name,
+            options,
+            retry_backend,
+        } = self.layer.clone();
+        let attempt = request.parts.attempt.current();
+        let task_id = request.parts.task_id.to_string();
+        let request_id = request
+            .parts
+            .data
+            .get::<JobRequestId>()
+            .map(JobRequestId::as_str)
+            .unwrap_or_default();
+        let span = tracing::info_span!("job", name, task_id = %request.parts.task_id, attempt, request_id);
+        let retry_request = request.clone();
+
+        job_status_tracker().running(&task_id, name, attempt);
+
+        let call = call_with_timeout(self.inner.call(request), options.timeout);
+
+        Box::pin(
+            async move {
+                let error = match call.await {
+                    Ok(response) => {
+                        job_status_tracker().succeeded(&task_id, name, attempt);
+
+                        return Ok(response);
+                    }
+                    Err(error @ Error::Abort(_)) => error,
+                    Err(error) if attempt > options.retries => Error::Abort(Arc::new(Box::new(error))),
+                    Err(error) => match retry_backend {
+                        Some(retry_backend) => {
+                            let delay = JobError::from_apalis_error(&error)
+                                .and_then(JobError::retry_after_duration)
+                                .unwrap_or_else(|| options.backoff_for(attempt));
+
+                            match retry_backend.retry(retry_request.clone(), delay).await {
+                                Ok(()) => {
+                                    tracing::warn!(
+                                        "Job attempt {attempt} failed, retrying in {} ms: {error}",
+                                        delay.as_millis()
+                                    );
+                                    job_status_tracker().queued(&task_id, name);
+
+                                    return Err(error);
+                                }
+                                Err(retry_error) => {
+                                    tracing::error!("Could not reschedule job: {retry_error}");
+
+                                    Error::Abort(Arc::new(Box::new(error)))
+                                }
+                            }
+                        }
+                        None => Error::Abort(Arc::new(Box::new(error))),
+                    },
+                };
+
+                let first_attempt_at = job_status_tracker().first_attempt_at(&task_id).unwrap_or_else(Utc::now);
+
+                job_status_tracker().failed(&task_id, name, attempt, error.to_string());
+
+                if options.dead_letters {
+                    record_dead_letter(DeadLetter::new(
+                        task_id,
+                        &retry_request.args,
+                        &error,
+                        attempt,
+                        first_attempt_at,
+                    ))
+                    .await;
+                }
+
+                Err(error)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+async fn call_with_timeout<T>(
+    call: impl Future<Output = Result<T, Error>>,
+    timeout: Option<Duration>,
+) -> Result<T, Error> {
+    let Some(timeout) = timeout else {
+        return call.await;
+    };
+
+    let mut call = pin!(call);
+    let mut timer = pin!(apalis_core::sleep(timeout));
+
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(result) = call.as_mut().poll(cx) {
+            return Poll::Ready(result);
+        }
+
+        timer.as_mut().poll(cx).map(|_| {
+            Err(Error::Failed(Arc::new(
+                Box::new(JobTimeoutError(timeout)) as BoxDynError
+            )))
+        })
+    })
+    .await
+}
+
+pub type JobWorkerService<F, J, Ctx, Args> =
+    ConcurrencyLimit<JobRequestIdService<JobService<ServiceFn<F, J, Ctx, Args>, J, Ctx>>>;
+
+pub fn job_worker<J, Ctx, B, F, Args>(
+    backend: B,
+    handler: F,
+    options: JobOptions,
+) -> Worker<Ready<JobWorkerService<F, J, Ctx, Args>, B>>
+where
+    J: Job,
+    B: Backend<Request<J, Ctx>> + RetryBackend<J, Ctx> + Clone,
+{
+    let layer = JobLayer::new(J::NAME, options).retry_backend(backend.clone());
+
+    job_worker_with_layer(J::NAME, backend, handler, layer)
+}
+
+pub(crate) fn job_worker_with_layer<J, Ctx, B, F, Args>(
+    name: &'static str,
+    backend: B,
+    handler: F,
+    layer: JobLayer<J, Ctx>,
+) -> Worker<Ready<JobWorkerService<F, J, Ctx, Args>, B>>
+where
+    J: Job,
+    B: Backend<Request<J, Ctx>>,
+{
+    let service = ConcurrencyLimitLayer::new(layer.options.concurrency)
+        .layer(JobRequestIdLayer::new().layer(layer.layer(service_fn(handler))));
+
+    Worker::new(WorkerId::new(name), Ready::new(service, backend))
+}
+
+pub trait JobStorageExt: Storage {
+    fn push_job(&mut self, job: Self::Job) -> impl Future<Output = Result<Parts<Self::Context>, Self::Error>> + Send;
+}
+
+impl<S> JobStorageExt for S
+where
+    S: Storage + Send,
+    S::Job: Job,
+    S::Context: Send,
+{
+    async fn push_job(&mut self, job: Self::Job) -> Result<Parts<Self::Context>, Self::Error> {
+        let request_id = JobRequestId::current();
+        let mut request = Request::new(job);
+
+        if let Some(request_id) = &request_id {
+            request.parts.data.insert(request_id.clone());
+        }
+
+        let parts = self.push_request(request).await?;
+
+        track_queued(&parts.task_id, S::Job::NAME);
+
+        if let Some(request_id) = request_id {
+            job_status_tracker().set_request_id(&parts.task_id.to_string(), S::Job::NAME, request_id.as_str());
+        }
+
+        Ok(parts)
+    }
+}
+
+pub trait MonitorJobExt: Sized {
+    fn register_job<J, Ctx, B, F, Args>(self, backend: B, handler: F) -> Self
+    where
+        J: Job,
+        Ctx: Clone + Send + Sync + 'static,
+        B: Backend<Request<J, Ctx>> + RetryBackend<J, Ctx> + Clone + Send + 'static,
+        B::Stream: Unpin + Send + 'static,
+        B::Layer: Layer<JobWorkerService<F, J, Ctx, Args>> + Send,
+        <B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service: Service<Request<J, Ctx>> + Send,
+        <<B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service as Service<Request<J, Ctx>>>::Future: Send,
+        <<B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service as Service<Request<J, Ctx>>>::Error:
+            Send + Sync + Into<BoxDynError>,
+        ServiceFn<F, J, Ctx, Args>: Service<Request<J, Ctx>, Error = Error> + Clone + Send + 'static,
+        <ServiceFn<F, J, Ctx, Args> as Service<Request<J, Ctx>>>::Future: Send + 'static,
+        <ServiceFn<F, J, Ctx, Args> as Service<Request<J, Ctx>>>::Response: Send + 'static,
+    {
+        self.register_job_with(backend, handler, J::options())
+    }
+
+    fn register_job_with<J, Ctx, B, F, Args>(self, backend: B, handler: F, options: JobOptions) -> Self
+    where
+        J: Job,
+        Ctx: Clone + Send + Sync + 'static,
+        B: Backend<Request<J, Ctx>> + RetryBackend<J, Ctx> + Clone + Send + 'static,
+        B::Stream: Unpin + Send + 'static,
+        B::Layer: Layer<JobWorkerService<F, J, Ctx, Args>> + Send,
+        <B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service: Service<Request<J, Ctx>> + Send,
+        <<B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service as Service<Request<J, Ctx>>>::Future: Send,
+        <<B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service as Service<Request<J, Ctx>>>::Error:
+            Send + Sync + Into<BoxDynError>,
+        ServiceFn<F, J, Ctx, Args>: Service<Request<J, Ctx>, Error = Error> + Clone + Send + 'static,
+        <ServiceFn<F, J, Ctx, Args> as Service<Request<J, Ctx>>>::Future: Send + 'static,
+        <ServiceFn<F, J, Ctx, Args> as Service<Request<J, Ctx>>>::Response: Send + 'static;
+}
+
+impl MonitorJobExt for Monitor {
+    fn register_job_with<J, Ctx, B, F, Args>(self, backend: B, handler: F, options: JobOptions) -> Self
+    where
+        J: Job,
+        Ctx: Clone + Send + Sync + 'static,
+        B: Backend<Request<J, Ctx>> + RetryBackend<J, Ctx> + Clone + Send + 'static,
+        B::Stream: Unpin + Send + 'static,
+        B::Layer: Layer<JobWorkerService<F, J, Ctx, Args>> + Send,
+        <B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service: Service<Request<J, Ctx>> + Send,
+        <<B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service as Service<Request<J, Ctx>>>::Future: Send,
+        <<B::Layer as Layer<JobWorkerService<F, J, Ctx, Args>>>::Service as Service<Request<J, Ctx>>>::Error:
+            Send + Sync + Into<BoxDynError>,
+        ServiceFn<F, J, Ctx, Args>: Service<Request<J, Ctx>, Error = Error> + Clone + Send + 'static,
+        <ServiceFn<F, J, Ctx, Args> as Service<Request<J, Ctx>>>::Future: Send + 'static,
+        <ServiceFn<F, J, Ctx, Args> as Service<Request<J, Ctx>>>::Response: Send + 'static,
+    {
+        self.register(job_worker(backend, handler, options))
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use apalis::prelude::Attempt;
+    use serde::Deserialize;
+
+    use crate::job_status::{JobState, JobStatusQuery};
+    use crate::monitor::{JobResultExt, dead_letter_store, job_status_tracker};
+    use crate::test_utils::{JobHarness, JobRun, TestBackend, WorkerEvent};
+
+    use super::*;
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    struct FlakyJob {
+        failures: usize,
+    }
+
+    impl Job for FlakyJob {
+        const NAME: &'static str = "flaky";
+
+        fn options() -> JobOptions {
+            JobOptions::new()
+                .retries(2)
+                .backoff(Duration::from_secs(5), Duration::from_secs(60))
+        }
+    }
+
+    async fn flaky_job(job: FlakyJob, attempt: Attempt) -> Result<(), Error> {
+        if attempt.current() <= job.failures {
+            return Err::<(), _>("Temporary failure").retryable();
+        }
+
+        Ok(())
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    struct RejectedJob;
+
+    impl Job for RejectedJob {
+        const NAME: &'static str = "rejected";
+    }
+
+    async fn rejected_job(_job: RejectedJob) -> Result<(), Error> {
+        Err::<(), _>("Invalid payload").permanent()
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    struct StuckJob;
+
+    impl Job for StuckJob {
+        const NAME: &'static str = "stuck";
+
+        fn options() -> JobOptions {
+            JobOptions::new().retries(1).timeout(Duration::from_millis(10))
+        }
+    }
+
+    async fn stuck_job(_job: StuckJob) -> Result<(), Error> {
+        std::future::pending().await
+    }
+
+    fn run(harness: JobHarness) -> JobRun {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(harness.run_until_idle())
+    }
+
+    #[test]
+    fn failed_attempts_are_rescheduled_through_the_backend() {
+        let backend = TestBackend::new();
+        let task_id = backend.enqueue(FlakyJob { failures: 2 });
+
+        let run = run(JobHarness::new().register_job(&backend, flaky_job));
+
+        let outcome = backend.outcome(&task_id).unwrap();
+        let status = job_status_tracker().job_status(&task_id.to_string()).unwrap();
+
+        assert!(outcome.is_success());
+        assert_eq!(outcome.attempts, 3);
+        assert_eq!(backend.outcomes().len(), 1);
+        assert_eq!(status.job_name, FlakyJob::NAME);
+        assert_eq!(status.state, JobState::Succeeded);
+        assert_eq!(status.attempts, 3);
+        assert_eq!(run.worker_events(FlakyJob::NAME).first(), Some(&WorkerEvent::Start));
+        assert_eq!(
+            backend.retry_delays(),
+            [Duration::from_secs(5), Duration::from_secs(10)]
+        );
+    }
+
+    #[test]
+    fn permanent_errors_are_not_retried() {
+        let backend = TestBackend::new();
+        let task_id = backend.enqueue(RejectedJob);
+
+        let run = run(JobHarness::new().register_job(&backend, rejected_job));
+
+        let outcome = backend.outcome(&task_id).unwrap();
+        let status = job_status_tracker().job_status(&task_id.to_string()).unwrap();
+
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.attempts, 1);
+        assert!(backend.retry_delays().is_empty());
+        assert_eq!(status.state, JobState::Failed);
+        assert_eq!(status.error.as_deref(), Some("AbortError: Invalid payload"));
+        assert!(run.has_event(RejectedJob::NAME, &WorkerEvent::Engage(task_id.to_string())));
+        assert!(run.has_event(RejectedJob::NAME, &WorkerEvent::Idle));
+    }
+
+    #[test]
+    fn timed_out_attempts_are_retried_then_failed() {
+        let backend = TestBackend::new();
+        let task_id = backend.enqueue(StuckJob);
+
+        let run = run(JobHarness::new().register_job(&backend, stuck_job));
+
+        let outcome = backend.outcome(&task_id).unwrap();
+        let engaged = run
+            .worker_events(StuckJob::NAME)
+            .into_iter()
+            .filter(|event| matches!(event, WorkerEvent::Engage(_)))
+            .count();
+
+        assert_eq!(outcome.attempts, 2);
+        assert!(outcome.error.unwrap().contains("Job timed out after 10 ms"));
+        assert_eq!(backend.retry_delays(), [Duration::from_secs(1)]);
+        assert_eq!(engaged, 2);
+    }
+
+    #[test]
+    fn exhausted_jobs_are_dead_lettered() {
+        let backend = TestBackend::new();
+        let task_id = backend.enqueue(FlakyJob { failures: 10 });
+
+        run(JobHarness::new().register_job(&backend, flaky_job));
+
+        let outcome = backend.outcome(&task_id).unwrap();
+        let dead_letter = dead_letter_store()
+            .list()
+            .unwrap()
+            .into_iter()
+            .find(|letter| letter.task_id == task_id.to_string())
+            .unwrap();
+
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.attempts, 3);
+        assert_eq!(dead_letter.attempts, 3);
+        assert_eq!(
+            Some(dead_letter.first_attempt_at),
+            job_status_tracker().first_attempt_at(&task_id.to_string())
+        );
+        assert_eq!(backend.retry_delays().len(), 2);
+    }
+}
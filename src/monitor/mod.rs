@@ -1,7 +1,30 @@
-use std::time::Duration;
+use std::fmt::Display;
 
-use apalis::prelude::{BoxDynError, Error, Event, Monitor};
-use tracing::{error, info};
+use apalis::prelude::{BoxDynError, Error, Event, Monitor, Worker};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, trace, warn};
+
+use crate::core::config::{MONITOR_CONFIG, MonitorConfig};
+
+#[cfg(feature = "metrics")]
+use crate::metrics::Counter;
+
+mod dead_letter;
+mod error;
+mod job;
+mod request_id;
+mod schedule;
+mod status;
+
+pub use dead_letter::*;
+pub use error::*;
+pub use job::*;
+pub use request_id::*;
+pub use schedule::*;
+pub use status::*;
+
+#[cfg(feature = "metrics")]
+const WORKER_EVENTS: Counter = Counter::new("apalis_worker_events_total", "Total number of apalis worker events.");
 
 pub trait OrApalisError<T> {
     fn or_apalis_error(self) -> Result<T, Error>;
@@ -16,38 +39,132 @@ impl<T, E: std::error::Error + Send + Sync + 'static> OrApalisError<T> for Resul
     }
 }
 
+type EventHook = Box<dyn Fn(&Worker<Event>) + Send + Sync>;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn log(&self, message: impl Display) {
+        match self {
+            Self::Off => {}
+            Self::Trace => trace!("{message}"),
+            Self::Debug => debug!("{message}"),
+            Self::Info => info!("{message}"),
+            Self::Warn => warn!("{message}"),
+            Self::Error => error!("{message}"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EventHooks {
+    hooks: Vec<EventHook>,
+}
+
+impl EventHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_event<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Worker<Event>) + Send + Sync + 'static,
+    {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    fn call(&self, event: &Worker<Event>) {
+        for hook in &self.hooks {
+            hook(event);
+        }
+    }
+}
+
 pub trait MonitorExt {
     fn setup() -> Self;
+
+    fn setup_with(config: &MonitorConfig) -> Self;
+
+    fn setup_with_hooks(config: &MonitorConfig, hooks: EventHooks) -> Self;
 }
 
 impl MonitorExt for Monitor {
     fn setup() -> Self {
+        Self::setup_with(&MONITOR_CONFIG)
+    }
+
+    fn setup_with(config: &MonitorConfig) -> Self {
+        Self::setup_with_hooks(config, EventHooks::default())
+    }
+
+    fn setup_with_hooks(config: &MonitorConfig, hooks: EventHooks) -> Self {
+        let shutdown_timeout = config.shutdown_timeout();
+        let config = config.clone();
+
         Monitor::new()
-            .on_event(|e| {
+            .on_event(move |e| {
                 let worker_id = e.id();
+
+                #[cfg(feature = "metrics")]
+                WORKER_EVENTS.increment(&[("worker", &worker_id.to_string()), ("event", event_name(e.inner()))]);
+
                 match e.inner() {
                     Event::Engage(task_id) => {
-                        info!("Worker [{worker_id}] got a job with id: {task_id}");
+                        config
+                            .engage_log_level
+                            .log(format_args!("Worker [{worker_id}] got a job with id: {task_id}"));
                     }
                     Event::Error(e) => {
-                        error!("Worker [{worker_id}] encountered an error: {e}");
+                        config
+                            .error_log_level
+                            .log(format_args!("Worker [{worker_id}] encountered an error: {e}"));
+                    }
+                    Event::Custom(message) => {
+                        config
+                            .custom_log_level
+                            .log(format_args!("Worker [{worker_id}] {message}"));
                     }
-
                     Event::Exit => {
-                        info!("Worker [{worker_id}] exited");
+                        config.exit_log_level.log(format_args!("Worker [{worker_id}] exited"));
                     }
                     Event::Idle => {
-                        info!("Worker [{worker_id}] is idle");
+                        if config.log_idle_events {
+                            config.idle_log_level.log(format_args!("Worker [{worker_id}] is idle"));
+                        }
                     }
                     Event::Start => {
-                        info!("Worker [{worker_id}] started");
+                        config.start_log_level.log(format_args!("Worker [{worker_id}] started"));
                     }
                     Event::Stop => {
-                        info!("Worker [{worker_id}] stopped");
+                        config.stop_log_level.log(format_args!("Worker [{worker_id}] stopped"));
                     }
-                    _ => {}
                 }
+
+                hooks.call(&e);
             })
-            .shutdown_timeout(Duration::from_millis(5000))
+            .shutdown_timeout(shutdown_timeout)
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn event_name(event: &Event) -> &'static str {
+    match event {
+        Event::Engage(_) => "engage",
+        Event::Error(_) => "error",
+        Event::Custom(_) => "custom",
+        Event::Exit => "exit",
+        Event::Idle => "idle",
+        Event::Start => "start",
+        Event::Stop => "stop",
     }
 }
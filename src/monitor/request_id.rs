@@ -0,0 +1,138 @@
+use std::task::{Context as TaskContext, Poll};
+
+use apalis::prelude::{Context, Event, Request, Worker};
+use tower::{Layer, Service};
+
+use super::job_status_tracker;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobRequestId(pub String);
+
+impl JobRequestId {
+    pub fn current() -> Option<Self> {
+        #[cfg(feature = "server")]
+        return crate::server::current_request_id().map(Self);
+
+        #[cfg(not(feature = "server"))]
+        None
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct JobRequestIdLayer;
+
+impl JobRequestIdLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for JobRequestIdLayer {
+    type Service = JobRequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        JobRequestIdService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct JobRequestIdService<S> {
+    inner: S,
+}
+
+impl<S, J, Ctx> Service<Request<J, Ctx>> for JobRequestIdService<S>
+where
+    S: Service<Request<J, Ctx>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<J, Ctx>) -> Self::Future {
+        let task_id = request.parts.task_id.to_string();
+
+        if request.parts.data.get::<JobRequestId>().is_none()
+            && let Some(request_id) = job_status_tracker().request_id(&task_id)
+        {
+            request.parts.data.insert(JobRequestId(request_id));
+        }
+
+        if let (Some(JobRequestId(request_id)), Some(worker)) = (
+            request.parts.data.get::<JobRequestId>(),
+            request.parts.data.get::<Worker<Context>>(),
+        ) {
+            worker.emit(Event::Custom(format!(
+                "Job with id: {task_id} has request id: {request_id}"
+            )));
+        }
+
+        self.inner.call(request)
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use apalis::prelude::Error;
+    use serde::{Deserialize, Serialize};
+
+    use crate::monitor::Job;
+    use crate::test_utils::{JobHarness, TestBackend, WorkerEvent};
+
+    use super::*;
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    struct TracedJob;
+
+    impl Job for TracedJob {
+        const NAME: &'static str = "traced";
+    }
+
+    async fn traced_job(_job: TracedJob) -> Result<(), Error> {
+        Ok(())
+    }
+
+    #[test]
+    fn request_ids_are_propagated_to_worker_events() {
+        let backend = TestBackend::new();
+        let mut request = Request::new(TracedJob);
+
+        request.parts.data.insert(JobRequestId("request-1".to_owned()));
+
+        let from_parts = backend.enqueue_request(request);
+        let from_tracker = backend.enqueue(TracedJob);
+        let without = backend.enqueue(TracedJob);
+
+        job_status_tracker().set_request_id(&from_tracker.to_string(), TracedJob::NAME, "request-2");
+
+        let run = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(JobHarness::new().register_job(&backend, traced_job).run_until_idle());
+
+        let custom = |task_id: &dyn ToString, request_id: &str| {
+            WorkerEvent::Custom(format!(
+                "Job with id: {} has request id: {request_id}",
+                task_id.to_string()
+            ))
+        };
+        let custom_events = run
+            .worker_events(TracedJob::NAME)
+            .into_iter()
+            .filter(|event| matches!(event, WorkerEvent::Custom(_)))
+            .count();
+
+        assert!(run.has_event(TracedJob::NAME, &custom(&from_parts, "request-1")));
+        assert!(run.has_event(TracedJob::NAME, &custom(&from_tracker, "request-2")));
+        assert!(backend.outcome(&without).unwrap().is_success());
+        assert_eq!(custom_events, 2);
+    }
+}
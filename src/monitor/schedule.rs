@@ -0,0 +1,225 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use apalis::prelude::{Backend, Context, Error, Monitor, Poller, Request, ServiceFn, Worker};
+use apalis_core::codec::NoopCodec;
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use futures_util::stream::{self, BoxStream};
+use serde::{Deserialize, Serialize};
+use tower::Service;
+use tower::layer::util::Identity;
+
+use super::{Job, JobLayer, JobOptions, job_worker_with_layer};
+
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Debug)]
+pub enum Schedule {
+    Cron(Box<cron::Schedule>),
+    Interval(Duration),
+}
+
+impl Schedule {
+    pub fn cron(expression: &str) -> Result<Self, cron::error::Error> {
+        let expression = if expression.split_whitespace().count() == 5 {
+            format!("0 {expression}")
+        } else {
+            expression.to_owned()
+        };
+
+        Ok(Self::Cron(Box::new(cron::Schedule::from_str(&expression)?)))
+    }
+
+    pub fn interval(interval: Duration) -> Self {
+        Self::Interval(interval)
+    }
+
+    pub fn next_after(&self, now: DateTime<Utc>, last: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Cron(schedule) => schedule.after(&now).next(),
+            Self::Interval(interval) => {
+                // Ticks missed while the worker was busy or stopped are skipped, not replayed.
+                let next = last.unwrap_or(now) + *interval;
+
+                Some(next.max(now))
+            }
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = cron::error::Error;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Self::cron(expression)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ScheduledTick {
+    pub schedule: String,
+    pub scheduled_at: DateTime<Utc>,
+}
+
+impl Job for ScheduledTick {
+    const NAME: &'static str = "schedule";
+
+    fn options() -> JobOptions {
+        JobOptions::new().retries(0).dead_letters(false).concurrency(1)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ScheduleBackend {
+    name: &'static str,
+    schedule: Schedule,
+}
+
+impl ScheduleBackend {
+    pub fn new(name: &'static str, schedule: Schedule) -> Self {
+        Self { name, schedule }
+    }
+}
+
+impl Backend<Request<ScheduledTick, ()>> for ScheduleBackend {
+    type Stream = BoxStream<'static, Result<Option<Request<ScheduledTick, ()>>, Error>>;
+
+    type Layer = Identity;
+
+    type Codec = NoopCodec<Request<ScheduledTick, ()>>;
+
+    fn poll(self, worker: &Worker<Context>) -> Poller<Self::Stream, Self::Layer> {
+        let worker = worker.clone();
+        let stream = stream::unfold(None, move |last| {
+            let worker = worker.clone();
+            let schedule = self.schedule.clone();
+
+            async move {
+                if worker.is_shutting_down() {
+                    return None;
+                }
+
+                let scheduled_at = schedule.next_after(Utc::now(), last)?;
+
+                while let Ok(delay) = (scheduled_at - Utc::now()).to_std() {
+                    if worker.is_shutting_down() {
+                        return None;
+                    }
+
+                    apalis_core::sleep(delay.min(SHUTDOWN_CHECK_INTERVAL)).await;
+                }
+
+                if worker.is_shutting_down() {
+                    return None;
+                }
+
+                let tick = ScheduledTick {
+                    schedule: self.name.to_owned(),
+                    scheduled_at,
+                };
+
+                Some((Ok(Some(Request::new(tick))), Some(scheduled_at)))
+            }
+        });
+
+        Poller::new(stream.boxed(), std::future::pending())
+    }
+}
+
+pub trait MonitorScheduleExt: Sized {
+    fn register_schedule<F, Args>(self, name: &'static str, schedule: Schedule, handler: F) -> Self
+    where
+        ServiceFn<F, ScheduledTick, (), Args>:
+            Service<Request<ScheduledTick, ()>, Error = Error> + Clone + Send + 'static,
+        <ServiceFn<F, ScheduledTick, (), Args> as Service<Request<ScheduledTick, ()>>>::Future: Send + 'static,
+        <ServiceFn<F, ScheduledTick, (), Args> as Service<Request<ScheduledTick, ()>>>::Response: Send + 'static,
+    {
+        self.register_schedule_with(name, schedule, handler, ScheduledTick::options())
+    }
+
+    fn register_schedule_with<F, Args>(
+        self,
+        name: &'static str,
+        schedule: Schedule,
+        handler: F,
+        options: JobOptions,
+    ) -> Self
+    where
+        ServiceFn<F, ScheduledTick, (), Args>:
+            Service<Request<ScheduledTick, ()>, Error = Error> + Clone + Send + 'static,
+        <ServiceFn<F, ScheduledTick, (), Args> as Service<Request<ScheduledTick, ()>>>::Future: Send + 'static,
+        <ServiceFn<F, ScheduledTick, (), Args> as Service<Request<ScheduledTick, ()>>>::Response: Send + 'static;
+}
+
+impl MonitorScheduleExt for Monitor {
+    fn register_schedule_with<F, Args>(
+        self,
+        name: &'static str,
+        schedule: Schedule,
+        handler: F,
+        options: JobOptions,
+    ) -> Self
+    where
+        ServiceFn<F, ScheduledTick, (), Args>:
+            Service<Request<ScheduledTick, ()>, Error = Error> + Clone + Send + 'static,
+        <ServiceFn<F, ScheduledTick, (), Args> as Service<Request<ScheduledTick, ()>>>::Future: Send + 'static,
+        <ServiceFn<F, ScheduledTick, (), Args> as Service<Request<ScheduledTick, ()>>>::Response: Send + 'static,
+    {
+        self.register(job_worker_with_layer(
+            name,
+            ScheduleBackend::new(name, schedule),
+            handler,
+            JobLayer::new(name, options.concurrency(1)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    async fn tick(_: ScheduledTick) -> Result<(), Error> {
+        Ok(())
+    }
+
+    #[test]
+    fn interval_skips_missed_ticks() {
+        let now = Utc::now();
+        let schedule = Schedule::interval(Duration::from_secs(60));
+
+        assert_eq!(
+            schedule.next_after(now, Some(now - chrono::Duration::hours(1))),
+            Some(now)
+        );
+        assert_eq!(
+            schedule.next_after(now, Some(now)),
+            Some(now + chrono::Duration::seconds(60))
+        );
+    }
+
+    #[test]
+    fn waiting_schedules_stop_on_shutdown() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let started_at = Instant::now();
+
+        runtime.block_on(async {
+            Monitor::new()
+                .register_schedule("hourly", Schedule::interval(Duration::from_secs(3600)), tick)
+                .run_with_signal(async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+
+                    Ok(())
+                })
+                .await
+                .unwrap();
+        });
+
+        assert!(started_at.elapsed() < Duration::from_secs(5));
+    }
+}
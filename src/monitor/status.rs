@@ -0,0 +1,129 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use chrono::{DateTime, Utc};
+
+use crate::job_status::{JobState, JobStatusInfo, JobStatusQuery};
+
+const DEFAULT_CAPACITY: usize = 10_000;
+
+static JOB_STATUS_TRACKER: LazyLock<Arc<JobStatusTracker>> = LazyLock::new(Arc::default);
+
+pub struct JobStatusTracker {
+    capacity: usize,
+    statuses: Mutex<(HashMap<String, TrackedJob>, VecDeque<String>)>,
+}
+
+struct TrackedJob {
+    info: JobStatusInfo,
+    first_attempt_at: Option<DateTime<Utc>>,
+    request_id: Option<String>,
+}
+
+impl Default for JobStatusTracker {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl JobStatusTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            statuses: Mutex::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+
+    fn update(&self, task_id: &str, job_name: &str, update: impl FnOnce(&mut TrackedJob)) {
+        let Ok(mut guard) = self.statuses.lock() else {
+            return;
+        };
+        let (statuses, order) = &mut *guard;
+
+        if !statuses.contains_key(task_id) {
+            while statuses.len() >= self.capacity.max(1) {
+                let Some(oldest) = order.pop_front() else {
+                    break;
+                };
+
+                statuses.remove(&oldest);
+            }
+
+            order.push_back(task_id.to_owned());
+        }
+
+        let status = statuses.entry(task_id.to_owned()).or_insert_with(|| TrackedJob {
+            info: JobStatusInfo {
+                task_id: task_id.to_owned(),
+                job_name: job_name.to_owned(),
+                state: JobState::Queued,
+                attempts: 0,
+                error: None,
+            },
+            first_attempt_at: None,
+            request_id: None,
+        });
+
+        update(status);
+    }
+
+    pub fn queued(&self, task_id: &str, job_name: &str) {
+        self.update(task_id, job_name, |status| status.info.state = JobState::Queued);
+    }
+
+    pub fn running(&self, task_id: &str, job_name: &str, attempts: usize) {
+        self.update(task_id, job_name, |status| {
+            status.info.state = JobState::Running;
+            status.info.attempts = status.info.attempts.max(attempts);
+            status.first_attempt_at.get_or_insert_with(Utc::now);
+        });
+    }
+
+    pub fn succeeded(&self, task_id: &str, job_name: &str, attempts: usize) {
+        self.update(task_id, job_name, |status| {
+            status.info.state = JobState::Succeeded;
+            status.info.attempts = attempts;
+            status.info.error = None;
+        });
+    }
+
+    pub fn failed(&self, task_id: &str, job_name: &str, attempts: usize, error: String) {
+        self.update(task_id, job_name, |status| {
+            status.info.state = JobState::Failed;
+            status.info.attempts = attempts;
+            status.info.error = Some(error);
+        });
+    }
+
+    pub fn set_request_id(&self, task_id: &str, job_name: &str, request_id: &str) {
+        self.update(task_id, job_name, |status| {
+            status.request_id = Some(request_id.to_owned())
+        });
+    }
+
+    pub fn first_attempt_at(&self, task_id: &str) -> Option<DateTime<Utc>> {
+        self.statuses.lock().ok()?.0.get(task_id)?.first_attempt_at
+    }
+
+    pub fn request_id(&self, task_id: &str) -> Option<String> {
+        self.statuses.lock().ok()?.0.get(task_id)?.request_id.clone()
+    }
+}
+
+impl JobStatusQuery for JobStatusTracker {
+    fn job_status(&self, task_id: &str) -> Option<JobStatusInfo> {
+        Some(self.statuses.lock().ok()?.0.get(task_id)?.info.clone())
+    }
+}
+
+pub fn job_status_tracker() -> Arc<JobStatusTracker> {
+    JOB_STATUS_TRACKER.clone()
+}
+
+pub fn track_queued(task_id: &impl ToString, job_name: &str) {
+    JOB_STATUS_TRACKER.queued(&task_id.to_string(), job_name);
+}
@@ -0,0 +1,122 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::extract::Request;
+use axum::response::{IntoResponse, Response};
+use http::Method;
+use tower::{Layer, Service};
+
+use crate::constants::HEADER_APP_TOKEN;
+use crate::core::app_token::verify_app_token;
+
+use super::{ApiError, matches_prefix};
+
+#[derive(Clone)]
+pub struct AppTokenLayer {
+    protected: Arc<Vec<String>>,
+    allowed: Arc<Vec<String>>,
+}
+
+impl Default for AppTokenLayer {
+    fn default() -> Self {
+        Self {
+            protected: Arc::new(vec!["/api".to_owned()]),
+            allowed: Arc::new(
+                ["/healthz", "/livez", "/readyz", "/version", "/webhooks"]
+                    .map(ToOwned::to_owned)
+                    .to_vec(),
+            ),
+        }
+    }
+}
+
+impl AppTokenLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn protect_only(mut self, prefixes: &[&str]) -> Self {
+        self.protected = Arc::new(prefixes.iter().map(|prefix| (*prefix).to_owned()).collect());
+        self
+    }
+
+    pub fn protect(mut self, prefix: &str) -> Self {
+        Arc::make_mut(&mut self.protected).push(prefix.to_owned());
+        self
+    }
+
+    pub fn allow(mut self, prefix: &str) -> Self {
+        Arc::make_mut(&mut self.allowed).push(prefix.to_owned());
+        self
+    }
+
+    pub fn is_protected(&self, path: &str) -> bool {
+        self.protected.iter().any(|prefix| matches_prefix(path, prefix))
+            && !self.allowed.iter().any(|prefix| matches_prefix(path, prefix))
+    }
+}
+
+impl<S> Layer<S> for AppTokenLayer {
+    type Service = AppTokenService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AppTokenService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AppTokenService<S> {
+    inner: S,
+    layer: AppTokenLayer,
+}
+
+impl<S> Service<Request> for AppTokenService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        if request.method() != Method::OPTIONS && self.layer.is_protected(request.uri().path()) {
+            let is_valid = request
+                .headers()
+                .get(HEADER_APP_TOKEN)
+                .and_then(|value| value.to_str().ok())
+                .and_then(verify_app_token)
+                .is_some();
+
+            if !is_valid {
+                return Box::pin(async { Ok(ApiError::forbidden().detail("Invalid app token").into_response()) });
+            }
+        }
+
+        Box::pin(self.inner.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_and_webhook_routes_are_allowed_by_default() {
+        let layer = AppTokenLayer::new().protect_only(&["/"]);
+
+        assert!(layer.is_protected("/api/users"));
+
+        for path in ["/healthz", "/livez", "/readyz", "/version", "/webhooks/github"] {
+            assert!(!layer.is_protected(path), "{path} should not require an app token");
+        }
+    }
+}
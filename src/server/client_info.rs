@@ -0,0 +1,303 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::LazyLock;
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use http::HeaderMap;
+use http::header::FORWARDED;
+use http::request::Parts;
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::HEADER_USER_AGENT;
+use crate::core::config::SERVER_CONFIG;
+
+static TRUSTED_PROXIES: LazyLock<Vec<IpNet>> = LazyLock::new(|| {
+    SERVER_CONFIG
+        .trusted_proxies
+        .iter()
+        .map(|proxy| {
+            proxy
+                .parse::<IpNet>()
+                .or_else(|_| proxy.parse::<IpAddr>().map(IpNet::from))
+                .expect("Could not parse trusted proxy")
+        })
+        .collect()
+});
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ClientInfo {
+    pub ip: Option<IpAddr>,
+    pub user_agent: UserAgent,
+}
+
+impl ClientInfo {
+    pub fn from_parts(headers: &HeaderMap, peer_ip: Option<IpAddr>) -> Self {
+        Self::from_parts_with(headers, peer_ip, &SERVER_CONFIG.client_ip_header, &TRUSTED_PROXIES)
+    }
+
+    pub fn from_parts_with(
+        headers: &HeaderMap,
+        peer_ip: Option<IpAddr>,
+        client_ip_header: &str,
+        trusted_proxies: &[IpNet],
+    ) -> Self {
+        let user_agent = headers
+            .get(HEADER_USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(UserAgent::parse)
+            .unwrap_or_default();
+
+        Self {
+            ip: resolve_client_ip(headers, peer_ip, client_ip_header, trusted_proxies),
+            user_agent,
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer_ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip());
+
+        Ok(Self::from_parts(&parts.headers, peer_ip))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceClass {
+    Bot,
+    Desktop,
+    Mobile,
+    Tablet,
+    #[default]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct UserAgent {
+    pub raw: String,
+    pub browser: Option<String>,
+    pub browser_version: Option<String>,
+    pub os: Option<String>,
+    pub device_class: DeviceClass,
+}
+
+impl UserAgent {
+    pub fn parse(raw: &str) -> Self {
+        let lowercase = raw.to_lowercase();
+
+        let browser = [
+            ("Edg/", "Edge"),
+            ("OPR/", "Opera"),
+            ("SamsungBrowser/", "Samsung Internet"),
+            ("Firefox/", "Firefox"),
+            ("FxiOS/", "Firefox"),
+            ("CriOS/", "Chrome"),
+            ("Chrome/", "Chrome"),
+            ("Version/", "Safari"),
+        ]
+        .into_iter()
+        .find_map(|(token, name)| {
+            raw.split_once(token).map(|(_, rest)| {
+                let version = rest
+                    .split(|char: char| !char.is_ascii_digit() && char != '.')
+                    .next()
+                    .filter(|version| !version.is_empty())
+                    .map(|version| version.to_owned());
+
+                (name.to_owned(), version)
+            })
+        })
+        .filter(|(name, _)| name != "Safari" || raw.contains("Safari/"));
+
+        let os = [
+            ("Windows", "Windows"),
+            ("iPhone", "iOS"),
+            ("iPad", "iOS"),
+            ("iPod", "iOS"),
+            ("Android", "Android"),
+            ("CrOS", "ChromeOS"),
+            ("Mac OS X", "macOS"),
+            ("Macintosh", "macOS"),
+            ("Linux", "Linux"),
+        ]
+        .into_iter()
+        .find(|(token, _)| raw.contains(token))
+        .map(|(_, name)| name.to_owned());
+
+        let device_class = if ["bot", "crawler", "spider", "curl/", "wget/"]
+            .iter()
+            .any(|token| lowercase.contains(token))
+        {
+            DeviceClass::Bot
+        } else if lowercase.contains("ipad")
+            || lowercase.contains("tablet")
+            || (lowercase.contains("android") && !lowercase.contains("mobile"))
+        {
+            DeviceClass::Tablet
+        } else if lowercase.contains("mobi") || lowercase.contains("iphone") || lowercase.contains("ipod") {
+            DeviceClass::Mobile
+        } else if os.is_some() {
+            DeviceClass::Desktop
+        } else {
+            DeviceClass::Unknown
+        };
+
+        let (browser, browser_version) = browser.unzip();
+
+        Self {
+            raw: raw.to_owned(),
+            browser,
+            browser_version: browser_version.flatten(),
+            os,
+            device_class,
+        }
+    }
+}
+
+fn is_trusted(ip: &IpAddr, trusted_proxies: &[IpNet]) -> bool {
+    trusted_proxies.iter().any(|proxy| proxy.contains(ip))
+}
+
+fn resolve_client_ip(
+    headers: &HeaderMap,
+    peer_ip: Option<IpAddr>,
+    client_ip_header: &str,
+    trusted_proxies: &[IpNet],
+) -> Option<IpAddr> {
+    let peer_ip = peer_ip?;
+
+    if client_ip_header.is_empty() || !is_trusted(&peer_ip, trusted_proxies) {
+        return Some(peer_ip);
+    }
+
+    let is_forwarded = client_ip_header.eq_ignore_ascii_case(FORWARDED.as_str());
+    let chain = header_values(headers, client_ip_header)
+        .flat_map(|value| value.split(',').map(str::to_owned).collect::<Vec<_>>())
+        .map(|element| {
+            if is_forwarded {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.trim().split_once('=')?;
+
+                    key.trim().eq_ignore_ascii_case("for").then(|| value.to_owned())
+                })
+            } else {
+                Some(element)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut client_ip = peer_ip;
+
+    for hop in chain.iter().rev() {
+        let Some(ip) = hop.as_deref().and_then(parse_ip) else {
+            return Some(peer_ip);
+        };
+
+        client_ip = ip;
+
+        if !is_trusted(&ip, trusted_proxies) {
+            break;
+        }
+    }
+
+    Some(client_ip)
+}
+
+fn header_values<'a>(headers: &'a HeaderMap, name: &str) -> impl Iterator<Item = String> + 'a {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+
+    if let Ok(ip) = value.parse() {
+        return Some(ip);
+    }
+
+    if let Ok(address) = value.parse::<SocketAddr>() {
+        return Some(address.ip());
+    }
+
+    value
+        .strip_prefix('[')
+        .and_then(|value| value.split_once(']'))
+        .and_then(|(ip, _)| ip.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+    fn resolve(name: &str, value: &str, client_ip_header: &str) -> Option<IpAddr> {
+        let mut headers = HeaderMap::new();
+        let trusted_proxies = ["10.0.0.0/8".parse().unwrap()];
+
+        headers.insert(
+            http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            HeaderValue::from_str(value).unwrap(),
+        );
+
+        resolve_client_ip(
+            &headers,
+            Some("10.0.0.1".parse().unwrap()),
+            client_ip_header,
+            &trusted_proxies,
+        )
+    }
+
+    #[test]
+    fn client_ip_is_read_from_the_configured_header_only() {
+        assert_eq!(
+            resolve(X_FORWARDED_FOR, "203.0.113.7, 10.0.0.2", X_FORWARDED_FOR),
+            Some("203.0.113.7".parse().unwrap())
+        );
+        assert_eq!(
+            resolve("forwarded", "for=203.0.113.7", X_FORWARDED_FOR),
+            Some("10.0.0.1".parse().unwrap())
+        );
+        assert_eq!(
+            resolve("forwarded", "for=203.0.113.7;proto=https, for=10.0.0.2", "forwarded"),
+            Some("203.0.113.7".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn client_ip_falls_back_to_peer_on_unparseable_hop() {
+        assert_eq!(
+            resolve(X_FORWARDED_FOR, "198.51.100.1, unknown, 10.0.0.2", X_FORWARDED_FOR),
+            Some("10.0.0.1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn client_ip_ignores_headers_from_untrusted_peers() {
+        let mut headers = HeaderMap::new();
+
+        headers.insert(X_FORWARDED_FOR, HeaderValue::from_static("203.0.113.7"));
+
+        assert_eq!(
+            resolve_client_ip(
+                &headers,
+                Some("198.51.100.9".parse().unwrap()),
+                X_FORWARDED_FOR,
+                &["10.0.0.0/8".parse().unwrap()],
+            ),
+            Some("198.51.100.9".parse().unwrap())
+        );
+    }
+}
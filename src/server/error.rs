@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use axum::response::{IntoResponse, Json, Response};
+use http::header::CONTENT_TYPE;
+use http::{HeaderValue, StatusCode};
+use serde::{Deserialize, Serialize};
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+#[cfg(feature = "app")]
+use dioxus::fullstack::HttpError;
+
+#[cfg(feature = "app")]
+use crate::app::ActionError;
+
+use super::current_request_id;
+
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FieldError {
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ApiError {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<FieldError>>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            type_uri: "about:blank".to_owned(),
+            title: status.canonical_reason().unwrap_or("Unknown Error").to_owned(),
+            status: status.as_u16(),
+            detail: None,
+            trace_id: None,
+            errors: BTreeMap::new(),
+        }
+    }
+
+    pub fn bad_request() -> Self {
+        Self::new(StatusCode::BAD_REQUEST)
+    }
+
+    pub fn forbidden() -> Self {
+        Self::new(StatusCode::FORBIDDEN)
+    }
+
+    pub fn internal_server_error() -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    pub fn not_found() -> Self {
+        Self::new(StatusCode::NOT_FOUND)
+    }
+
+    pub fn too_many_requests() -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS)
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED)
+    }
+
+    pub fn unprocessable_entity() -> Self {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY)
+    }
+
+    pub fn type_uri(mut self, type_uri: &str) -> Self {
+        self.type_uri = type_uri.to_owned();
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_owned();
+        self
+    }
+
+    pub fn detail(mut self, detail: &str) -> Self {
+        self.detail = Some(detail.to_owned());
+        self
+    }
+
+    pub fn trace_id(mut self, trace_id: &str) -> Self {
+        self.trace_id = Some(trace_id.to_owned());
+        self
+    }
+
+    pub fn validation_errors(mut self, validation_errors: &ValidationErrors) -> Self {
+        collect_field_errors(&mut self.errors, "", validation_errors);
+
+        self
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {detail}", self.title),
+            None => write!(f, "{}", self.title),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl IntoResponse for ApiError {
+    fn into_response(mut self) -> Response {
+        if self.trace_id.is_none() {
+            self.trace_id = current_request_id().or_else(|| {
+                tracing::Span::current()
+                    .id()
+                    .map(|id| format!("{:016x}", id.into_u64()))
+            });
+        }
+
+        let mut response = (self.status_code(), Json(self)).into_response();
+
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE));
+
+        response
+    }
+}
+
+impl From<ValidationErrors> for ApiError {
+    fn from(validation_errors: ValidationErrors) -> Self {
+        Self::unprocessable_entity().validation_errors(&validation_errors)
+    }
+}
+
+#[cfg(feature = "app")]
+impl From<ActionError> for ApiError {
+    fn from(error: ActionError) -> Self {
+        let status = StatusCode::from_u16(error.code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        if status.is_server_error() {
+            tracing::error!("Action failed: {}", error.message);
+
+            return Self::new(status);
+        }
+
+        Self::new(status)
+            .detail(&error.message)
+            .validation_errors(&error.details)
+    }
+}
+
+#[cfg(feature = "app")]
+impl From<HttpError> for ApiError {
+    fn from(error: HttpError) -> Self {
+        let api_error = Self::new(error.status);
+
+        match error.message {
+            Some(message) => api_error.detail(&message),
+            None => api_error,
+        }
+    }
+}
+
+fn collect_field_errors(
+    errors: &mut BTreeMap<String, Vec<FieldError>>,
+    path: &str,
+    validation_errors: &ValidationErrors,
+) {
+    for (field, kind) in validation_errors.errors() {
+        let field_path = if path.is_empty() {
+            field.to_string()
+        } else {
+            format!("{path}.{field}")
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                errors
+                    .entry(field_path)
+                    .or_default()
+                    .extend(field_errors.iter().map(|error| FieldError {
+                        code: error.code.to_string(),
+                        message: error.message.as_ref().map(|message| message.to_string()),
+                    }));
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(errors, &field_path, nested),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(errors, &format!("{field_path}[{index}]"), nested);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use validator::ValidationError;
+
+    use super::*;
+
+    #[test]
+    fn validation_errors_include_nested_paths() {
+        let mut address = ValidationErrors::new();
+        let mut item = ValidationErrors::new();
+        let mut errors = ValidationErrors::new();
+
+        address.add("city", ValidationError::new("required"));
+        item.add("name", ValidationError::new("length"));
+        errors.add("email", ValidationError::new("email"));
+        errors.merge_self("address", Err(address));
+        errors
+            .errors_mut()
+            .insert("items".into(), ValidationErrorsKind::List([(1, Box::new(item))].into()));
+
+        let api_error = ApiError::unprocessable_entity().validation_errors(&errors);
+
+        assert_eq!(
+            api_error.errors.keys().collect::<Vec<_>>(),
+            ["address.city", "email", "items[1].name"]
+        );
+        assert_eq!(api_error.errors["items[1].name"][0].code, "length");
+    }
+
+    #[cfg(feature = "app")]
+    #[test]
+    fn internal_action_errors_are_hidden() {
+        let io_error = ApiError::from(ActionError::from(std::io::Error::other("disk at /var/secret is full")));
+        let action_error = ApiError::from(ActionError::new("Name is taken", None));
+
+        assert_eq!(io_error.status, 500);
+        assert_eq!(io_error.detail, None);
+        assert_eq!(action_error.status, 422);
+        assert_eq!(action_error.detail.as_deref(), Some("Name is taken"));
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::extract::State;
+use axum::response::{IntoResponse, Json};
+use axum::routing::get;
+use futures_util::future::join_all;
+use http::StatusCode;
+use serde::Serialize;
+
+use crate::core::config::APP_CONFIG;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+type CheckFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+pub trait ReadinessCheck: Send + Sync + 'static {
+    fn check(&self) -> CheckFuture;
+}
+
+impl<F, Fut> ReadinessCheck for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    fn check(&self) -> CheckFuture {
+        Box::pin(self())
+    }
+}
+
+pub fn health_router(build_info: &'static [(&'static str, &'static str)]) -> HealthRouter {
+    HealthRouter::new(build_info)
+}
+
+#[derive(Clone)]
+pub struct HealthRouter {
+    checks: Vec<(String, Arc<dyn ReadinessCheck>)>,
+    build_info: &'static [(&'static str, &'static str)],
+}
+
+impl HealthRouter {
+    pub fn new(build_info: &'static [(&'static str, &'static str)]) -> Self {
+        Self {
+            checks: Vec::new(),
+            build_info,
+        }
+    }
+
+    pub fn check(mut self, name: &str, check: impl ReadinessCheck) -> Self {
+        self.checks.push((name.to_owned(), Arc::new(check)));
+        self
+    }
+
+    #[cfg(feature = "identity-client")]
+    pub fn identity_provider(self) -> Self {
+        use crate::core::identity_client::IdentityClient;
+
+        self.check("identity_provider", || async {
+            IdentityClient::new().ping().await.map_err(|error| error.to_string())
+        })
+    }
+
+    pub fn into_router(self) -> Router {
+        Router::new()
+            .route("/healthz", get(healthz))
+            .route("/readyz", get(readyz))
+            .route("/version", get(version))
+            .with_state(Arc::new(self))
+    }
+}
+
+impl From<HealthRouter> for Router {
+    fn from(health_router: HealthRouter) -> Self {
+        health_router.into_router()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum HealthStatus {
+    Ok,
+    Error,
+}
+
+#[derive(Serialize)]
+struct CheckReport {
+    status: HealthStatus,
+}
+
+#[derive(Serialize)]
+struct ReadinessReport {
+    status: HealthStatus,
+    checks: BTreeMap<String, CheckReport>,
+}
+
+#[derive(Serialize)]
+struct VersionReport {
+    name: String,
+    version: &'static str,
+    sdk_version: &'static str,
+    build: BTreeMap<&'static str, &'static str>,
+}
+
+async fn healthz() -> impl IntoResponse {
+    Json(CheckReport {
+        status: HealthStatus::Ok,
+    })
+}
+
+async fn readyz(State(health_router): State<Arc<HealthRouter>>) -> impl IntoResponse {
+    let results = join_all(health_router.checks.iter().map(|(name, check)| async move {
+        let result = match tokio::time::timeout(CHECK_TIMEOUT, check.check()).await {
+            Ok(result) => result,
+            Err(_) => Err("Timed out".to_owned()),
+        };
+
+        if let Err(error) = &result {
+            tracing::warn!("Readiness check {name} failed: {error}");
+        }
+
+        (name.clone(), result.is_ok())
+    }))
+    .await;
+
+    let is_ready = results.iter().all(|(_, is_ok)| *is_ok);
+    let checks = results
+        .into_iter()
+        .map(|(name, is_ok)| {
+            let status = if is_ok { HealthStatus::Ok } else { HealthStatus::Error };
+
+            (name, CheckReport { status })
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let status = if is_ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadinessReport {
+            status: if is_ready {
+                HealthStatus::Ok
+            } else {
+                HealthStatus::Error
+            },
+            checks,
+        }),
+    )
+}
+
+async fn version(State(health_router): State<Arc<HealthRouter>>) -> impl IntoResponse {
+    Json(VersionReport {
+        name: APP_CONFIG.title().to_owned(),
+        version: health_router
+            .build_info
+            .iter()
+            .find(|(name, _)| *name == "package_version")
+            .map_or("unknown", |(_, value)| value),
+        sdk_version: env!("CARGO_PKG_VERSION"),
+        build: health_router.build_info.iter().copied().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{Body, to_bytes};
+    use http::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[test]
+    fn readyz_hides_check_errors_and_version_reports_app_build_info() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let router = health_router(&[("package_version", "1.2.3")])
+            .check("ok", || async { Ok(()) })
+            .check("database", || async {
+                Err("password authentication failed".to_owned())
+            })
+            .into_router();
+
+        let (readyz, version) = runtime.block_on(async {
+            let readyz = router
+                .clone()
+                .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let version = router
+                .oneshot(Request::get("/version").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            (
+                (readyz.status(), to_bytes(readyz.into_body(), usize::MAX).await.unwrap()),
+                to_bytes(version.into_body(), usize::MAX).await.unwrap(),
+            )
+        });
+
+        let body: serde_json::Value = serde_json::from_slice(&readyz.1).unwrap();
+        let version: serde_json::Value = serde_json::from_slice(&version).unwrap();
+
+        assert_eq!(readyz.0, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["checks"]["database"], serde_json::json!({ "status": "error" }));
+        assert_eq!(body["checks"]["ok"], serde_json::json!({ "status": "ok" }));
+        assert_eq!(version["version"], "1.2.3");
+    }
+}
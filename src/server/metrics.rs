@@ -0,0 +1,83 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use axum::Router;
+use axum::extract::{MatchedPath, Request};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use http::header::CONTENT_TYPE;
+use tower::{Layer, Service};
+
+use crate::metrics::{Counter, Histogram, PROMETHEUS_CONTENT_TYPE, render_metrics};
+
+const HTTP_REQUESTS: Counter = Counter::new("http_requests_total", "Total number of HTTP requests.");
+const HTTP_REQUEST_DURATION: Histogram =
+    Histogram::new("http_request_duration_seconds", "HTTP request latency in seconds.");
+
+const UNMATCHED_PATH: &str = "<unmatched>";
+
+pub fn metrics_router() -> Router {
+    Router::new().route("/metrics", get(metrics_handler))
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    ([(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], render_metrics())
+}
+
+#[derive(Clone, Default)]
+pub struct MetricsLayer;
+
+impl MetricsLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct MetricsService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for MetricsService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let method = request.method().to_string();
+        let path = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|matched_path| matched_path.as_str().to_owned())
+            .unwrap_or_else(|| UNMATCHED_PATH.to_owned());
+        let started_at = Instant::now();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = future.await?;
+            let status = response.status().as_u16().to_string();
+
+            HTTP_REQUESTS.increment(&[("method", &method), ("path", &path), ("status", &status)]);
+            HTTP_REQUEST_DURATION.observe_duration(&[("method", &method), ("path", &path)], started_at.elapsed());
+
+            Ok(response)
+        })
+    }
+}
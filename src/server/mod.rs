@@ -0,0 +1,355 @@
+use std::future::IntoFuture;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::pin::Pin;
+
+use axum::Router;
+use axum::response::IntoResponse;
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use tokio::net::TcpListener;
+use tower::ServiceBuilder;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tower_http::trace::TraceLayer;
+use tracing::info;
+
+#[cfg(feature = "monitor")]
+use apalis::prelude::Monitor;
+#[cfg(feature = "app")]
+use dioxus::prelude::Element;
+
+use crate::constants::{X_APP_TOKEN, X_REQUEST_ID};
+#[cfg(feature = "monitor")]
+use crate::core::config::MONITOR_CONFIG;
+use crate::core::config::SERVER_CONFIG;
+
+mod app_token;
+mod client_info;
+mod error;
+mod health;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod rate_limit;
+mod request_id;
+mod security_headers;
+mod shutdown;
+mod static_files;
+
+pub use app_token::*;
+pub use client_info::*;
+pub use error::*;
+pub use health::*;
+#[cfg(feature = "metrics")]
+pub use metrics::*;
+pub use rate_limit::*;
+pub use request_id::*;
+pub use security_headers::*;
+pub use shutdown::*;
+pub use static_files::*;
+
+pub fn serve(router: Router) -> Server {
+    Server::new(router)
+}
+
+pub struct Server {
+    router: Router,
+    address: SocketAddr,
+    app_token_layer: Option<AppTokenLayer>,
+    security_headers_layer: Option<SecurityHeadersLayer>,
+    compression: bool,
+    #[cfg(feature = "metrics")]
+    metrics: bool,
+    shutdown: Option<ShutdownSignal>,
+}
+
+impl Server {
+    pub fn new(router: Router) -> Self {
+        Self {
+            router,
+            address: SERVER_CONFIG.address(),
+            app_token_layer: None,
+            security_headers_layer: Some(SecurityHeadersLayer::default()),
+            compression: true,
+            #[cfg(feature = "metrics")]
+            metrics: false,
+            shutdown: None,
+        }
+    }
+
+    pub fn address(mut self, address: SocketAddr) -> Self {
+        self.address = address;
+        self
+    }
+
+    pub fn app_token(mut self, layer: AppTokenLayer) -> Self {
+        self.app_token_layer = Some(layer);
+        self
+    }
+
+    pub fn security_headers(mut self, layer: Option<SecurityHeadersLayer>) -> Self {
+        self.security_headers_layer = layer;
+        self
+    }
+
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+
+    pub fn shutdown(mut self, signal: ShutdownSignal) -> Self {
+        self.shutdown = Some(signal);
+        self
+    }
+
+    pub fn static_dir(mut self, route: &str, path: impl AsRef<Path>) -> Self {
+        self.router = self.router.nest_service(
+            route,
+            ServiceBuilder::new()
+                .layer(StaticCacheLayer::new())
+                .map_response(IntoResponse::into_response)
+                .service(static_dir(path)),
+        );
+        self
+    }
+
+    #[cfg(feature = "app")]
+    pub fn dioxus_app(mut self, app: fn() -> Element) -> Self {
+        self.router = self.router.merge(dioxus::server::router(app));
+        self
+    }
+
+    pub fn into_router(self) -> Router {
+        let mut router = self.router;
+
+        #[cfg(feature = "metrics")]
+        if self.metrics {
+            router = router.merge(metrics_router());
+        }
+
+        if let Some(app_token_layer) = self.app_token_layer {
+            router = router.layer(app_token_layer);
+        }
+
+        if let Some(security_headers_layer) = self.security_headers_layer {
+            router = router.layer(security_headers_layer);
+        }
+
+        if self.compression {
+            router = router.layer(CompressionLayer::new());
+        }
+
+        #[cfg(feature = "metrics")]
+        if self.metrics {
+            router = router.layer(MetricsLayer::new());
+        }
+
+        if let Some(cors_layer) = cors_layer() {
+            router = router.layer(cors_layer);
+        }
+
+        router
+            .layer(TraceLayer::new_for_http().make_span_with(make_request_span))
+            .layer(RequestIdLayer::new())
+    }
+
+    pub async fn run(mut self) -> std::io::Result<()> {
+        let address = self.address;
+        let signal = self.shutdown.take().unwrap_or_else(ShutdownSignal::with_os_signals);
+        let listener = TcpListener::bind(address).await?;
+
+        info!("Listening on {address}");
+
+        let serve = axum::serve(
+            listener,
+            self.into_router().into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown({
+            let signal = signal.clone();
+
+            async move { signal.triggered().await }
+        })
+        .into_future();
+
+        drain_with_timeout("server", serve, &signal, SERVER_CONFIG.shutdown_timeout()).await
+    }
+
+    #[cfg(feature = "monitor")]
+    pub async fn run_with_monitor(mut self, monitor: Monitor) -> ShutdownOutcome {
+        let signal = self.shutdown.take().unwrap_or_else(ShutdownSignal::with_os_signals);
+
+        let server = {
+            let signal = signal.clone();
+
+            async move {
+                let result = self.shutdown(signal.clone()).run().await;
+
+                signal.trigger();
+
+                result
+            }
+        };
+
+        let monitor = {
+            let signal = signal.clone();
+
+            async move {
+                let run = monitor.run_with_signal({
+                    let signal = signal.clone();
+
+                    async move {
+                        signal.triggered().await;
+
+                        Ok(())
+                    }
+                });
+
+                drain_with_timeout("monitor", run, &signal, MONITOR_CONFIG.shutdown_timeout()).await
+            }
+        };
+
+        let (server, monitor) = futures_util::future::join(server, monitor).await;
+
+        ShutdownOutcome { server, monitor }
+    }
+}
+
+impl IntoFuture for Server {
+    type Output = std::io::Result<()>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.run())
+    }
+}
+
+pub fn cors_layer() -> Option<CorsLayer> {
+    if SERVER_CONFIG.cors_allowed_origins.is_empty() {
+        return None;
+    }
+
+    let allow_origin = AllowOrigin::list(
+        SERVER_CONFIG
+            .cors_allowed_origins
+            .iter()
+            .map(|origin| origin.parse().expect("Could not parse CORS allowed origin")),
+    );
+
+    let layer = CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(Any)
+        .allow_headers([AUTHORIZATION, CONTENT_TYPE, X_APP_TOKEN, X_REQUEST_ID])
+        .expose_headers([X_REQUEST_ID]);
+
+    Some(layer)
+}
+
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("Could not listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Could not listen for SIGTERM")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    info!("Shutdown signal received");
+}
+
+pub(crate) fn matches_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+
+    prefix.is_empty()
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::routing::get;
+    use http::Request;
+    use http::header::{ACCESS_CONTROL_ALLOW_ORIGIN, CACHE_CONTROL, ORIGIN};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[test]
+    fn cors_is_disabled_without_allowed_origins() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let router = serve(Router::new().route("/", get(|| async { "ok" }))).into_router();
+
+        let response = runtime
+            .block_on(
+                router.oneshot(
+                    Request::get("/")
+                        .header(ORIGIN, "https://evil.example")
+                        .body(Body::empty())
+                        .unwrap(),
+                ),
+            )
+            .unwrap();
+
+        assert!(cors_layer().is_none());
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[test]
+    fn only_static_dir_responses_are_cached_as_immutable() {
+        let dir = std::env::temp_dir().join(format!("sdk-static-dir-{}", std::process::id()));
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main-dxh0123abcd4567.js"), "console.log(1)").unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let router = serve(Router::new().route("/api/users/{id}", get(|| async { "user" })))
+            .static_dir("/assets", &dir)
+            .into_router();
+
+        let cache_control = |path: &'static str| {
+            let response = runtime
+                .block_on(router.clone().oneshot(Request::get(path).body(Body::empty()).unwrap()))
+                .unwrap();
+
+            response
+                .headers()
+                .get(CACHE_CONTROL)
+                .map(|value| value.to_str().unwrap().to_owned())
+        };
+
+        assert_eq!(
+            cache_control("/assets/main-dxh0123abcd4567.js").as_deref(),
+            Some("public, max-age=31536000, immutable")
+        );
+        assert_eq!(
+            cache_control("/api/users/123e4567-e89b-12d3-a456-426614174000.json"),
+            None
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
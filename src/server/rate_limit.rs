@@ -0,0 +1,443 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Request};
+use axum::response::{IntoResponse, Response};
+use http::HeaderValue;
+use http::header::RETRY_AFTER;
+use tower::{Layer, Service};
+use uuid::Uuid;
+
+use crate::constants::HEADER_APP_TOKEN;
+use crate::core::app_token::verify_app_token_with;
+use crate::core::config::APP_CONFIG;
+use crate::core::token::hash_token;
+
+use super::{ApiError, ClientInfo, matches_prefix};
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AuthenticatedUserId(pub Uuid);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RateLimitKey {
+    #[default]
+    Ip,
+    AppToken,
+    User,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RateLimitStrategy {
+    #[default]
+    TokenBucket,
+    SlidingWindow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub period: Duration,
+    pub strategy: RateLimitStrategy,
+}
+
+impl RateLimit {
+    #[track_caller]
+    pub fn new(requests: u32, period: Duration) -> Self {
+        assert!(!period.is_zero(), "Rate limit period must be greater than zero");
+
+        Self {
+            requests: requests.max(1),
+            period,
+            strategy: RateLimitStrategy::default(),
+        }
+    }
+
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    pub fn per_hour(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(3600))
+    }
+
+    pub fn strategy(mut self, strategy: RateLimitStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+}
+
+pub trait RateLimitStore: Send + Sync + 'static {
+    fn check(&self, key: &str, limit: &RateLimit) -> impl Future<Output = Result<(), Duration>> + Send;
+}
+
+struct RateLimitEntry {
+    period: Duration,
+    state: RateLimitState,
+}
+
+enum RateLimitState {
+    Bucket { tokens: f64, updated_at: Instant },
+    Window { hits: VecDeque<Instant> },
+}
+
+impl RateLimitEntry {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        let state = match limit.strategy {
+            RateLimitStrategy::TokenBucket => RateLimitState::Bucket {
+                tokens: limit.requests as f64,
+                updated_at: now,
+            },
+            RateLimitStrategy::SlidingWindow => RateLimitState::Window { hits: VecDeque::new() },
+        };
+
+        Self {
+            period: limit.period,
+            state,
+        }
+    }
+
+    fn hit(&mut self, limit: &RateLimit, now: Instant) -> Result<(), Duration> {
+        self.period = self.period.max(limit.period);
+
+        match &mut self.state {
+            RateLimitState::Bucket { tokens, updated_at } => {
+                let rate = limit.requests as f64 / limit.period.as_secs_f64();
+
+                *tokens = (*tokens + now.duration_since(*updated_at).as_secs_f64() * rate).min(limit.requests as f64);
+                *updated_at = now;
+
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    Ok(())
+                } else {
+                    Err(Duration::from_secs_f64((1.0 - *tokens) / rate))
+                }
+            }
+            RateLimitState::Window { hits } => {
+                while hits.front().is_some_and(|hit| now.duration_since(*hit) >= limit.period) {
+                    hits.pop_front();
+                }
+
+                if hits.len() < limit.requests as usize {
+                    hits.push_back(now);
+                    Ok(())
+                } else {
+                    Err((hits[0] + limit.period).duration_since(now))
+                }
+            }
+        }
+    }
+
+    fn is_stale(&self, now: Instant) -> bool {
+        match &self.state {
+            RateLimitState::Bucket { updated_at, .. } => now.duration_since(*updated_at) >= self.period,
+            RateLimitState::Window { hits } => hits.back().is_none_or(|hit| now.duration_since(*hit) >= self.period),
+        }
+    }
+}
+
+pub struct MemoryRateLimitStore {
+    entries: Mutex<HashMap<String, RateLimitEntry>>,
+    cleaned_at: Mutex<Instant>,
+}
+
+impl Default for MemoryRateLimitStore {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            cleaned_at: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn check_at(&self, key: &str, limit: &RateLimit, now: Instant) -> Result<(), Duration> {
+        let mut entries = self.entries.lock().expect("Could not lock rate limit entries");
+
+        {
+            let mut cleaned_at = self.cleaned_at.lock().expect("Could not lock rate limit cleanup");
+
+            if now.duration_since(*cleaned_at) >= CLEANUP_INTERVAL {
+                entries.retain(|_, entry| !entry.is_stale(now));
+                *cleaned_at = now;
+            }
+        }
+
+        entries
+            .entry(key.to_owned())
+            .or_insert_with(|| RateLimitEntry::new(limit, now))
+            .hit(limit, now)
+    }
+}
+
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn check(&self, key: &str, limit: &RateLimit) -> Result<(), Duration> {
+        self.check_at(key, limit, Instant::now())
+    }
+}
+
+pub struct RateLimitLayer<T = MemoryRateLimitStore> {
+    limit: RateLimit,
+    key: RateLimitKey,
+    paths: Arc<Vec<String>>,
+    store: Arc<T>,
+}
+
+impl<T> Clone for RateLimitLayer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            limit: self.limit,
+            key: self.key,
+            paths: self.paths.clone(),
+            store: self.store.clone(),
+        }
+    }
+}
+
+impl RateLimitLayer {
+    pub fn new(limit: RateLimit) -> Self {
+        Self::with_store(limit, MemoryRateLimitStore::new())
+    }
+}
+
+impl<T: RateLimitStore> RateLimitLayer<T> {
+    #[track_caller]
+    pub fn with_store(limit: RateLimit, store: T) -> Self {
+        assert!(!limit.period.is_zero(), "Rate limit period must be greater than zero");
+
+        Self {
+            limit,
+            key: RateLimitKey::default(),
+            paths: Arc::new(Vec::new()),
+            store: Arc::new(store),
+        }
+    }
+
+    pub fn key(mut self, key: RateLimitKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn path(mut self, prefix: &str) -> Self {
+        Arc::make_mut(&mut self.paths).push(prefix.to_owned());
+        self
+    }
+
+    fn applies_to(&self, path: &str) -> bool {
+        self.paths.is_empty() || self.paths.iter().any(|prefix| matches_prefix(path, prefix))
+    }
+
+    fn request_key(&self, request: &Request) -> Option<String> {
+        if self.key == RateLimitKey::User
+            && let Some(AuthenticatedUserId(user_id)) = request.extensions().get::<AuthenticatedUserId>()
+        {
+            return Some(format!("user:{user_id}"));
+        }
+
+        if self.key == RateLimitKey::AppToken {
+            let app_token = request
+                .headers()
+                .get(HEADER_APP_TOKEN)
+                .and_then(|value| value.to_str().ok())
+                .filter(|app_token| {
+                    verify_app_token_with(app_token, &APP_CONFIG.token, &APP_CONFIG.old_tokens).is_some()
+                });
+
+            if let Some(app_token) = app_token {
+                return Some(format!("app_token:{}", hash_token(app_token)));
+            }
+        }
+
+        let peer_ip = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip());
+
+        ClientInfo::from_parts(request.headers(), peer_ip)
+            .ip
+            .map(|ip| format!("ip:{ip}"))
+    }
+}
+
+impl<S, T> Layer<S> for RateLimitLayer<T> {
+    type Service = RateLimitService<S, T>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+pub struct RateLimitService<S, T = MemoryRateLimitStore> {
+    inner: S,
+    layer: RateLimitLayer<T>,
+}
+
+impl<S: Clone, T> Clone for RateLimitService<S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+impl<S, T> Service<Request> for RateLimitService<S, T>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    T: RateLimitStore,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        if !self.layer.applies_to(request.uri().path()) {
+            return Box::pin(self.inner.call(request));
+        }
+
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            let Some(key) = layer.request_key(&request) else {
+                tracing::error!(
+                    "Could not rate limit request without a client address, serve the router with `into_make_service_with_connect_info`"
+                );
+
+                return Ok(ApiError::internal_server_error().into_response());
+            };
+
+            if let Err(retry_after) = layer.store.check(&key, &layer.limit).await {
+                let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+                let mut response = ApiError::too_many_requests()
+                    .detail(&format!("Retry in {retry_after_secs} seconds"))
+                    .into_response();
+
+                response
+                    .headers_mut()
+                    .insert(RETRY_AFTER, HeaderValue::from(retry_after_secs));
+
+                return Ok(response);
+            }
+
+            inner.call(request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use http::StatusCode;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn request(peer: Option<&str>, app_token: &str) -> Request {
+        let mut request = Request::get("/")
+            .header(HEADER_APP_TOKEN, app_token)
+            .body(Body::empty())
+            .unwrap();
+
+        if let Some(peer) = peer {
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        }
+
+        request
+    }
+
+    #[test]
+    fn invalid_app_tokens_are_keyed_by_ip() {
+        let layer = RateLimitLayer::new(RateLimit::per_minute(1)).key(RateLimitKey::AppToken);
+
+        assert_eq!(
+            layer.request_key(&request(Some("198.51.100.1:4000"), "forged-1")),
+            Some("ip:198.51.100.1".to_owned())
+        );
+        assert_eq!(
+            layer.request_key(&request(Some("198.51.100.1:4000"), "forged-2")),
+            Some("ip:198.51.100.1".to_owned())
+        );
+    }
+
+    #[test]
+    fn authenticated_users_are_keyed_by_user_id_with_ip_fallback() {
+        let layer = RateLimitLayer::new(RateLimit::per_minute(1)).key(RateLimitKey::User);
+        let user_id = Uuid::new_v4();
+        let mut authenticated = request(Some("198.51.100.1:4000"), "");
+
+        authenticated.extensions_mut().insert(AuthenticatedUserId(user_id));
+
+        assert_eq!(layer.request_key(&authenticated), Some(format!("user:{user_id}")));
+        assert_eq!(
+            layer.request_key(&request(Some("198.51.100.1:4000"), "")),
+            Some("ip:198.51.100.1".to_owned())
+        );
+    }
+
+    #[test]
+    fn cleanup_keeps_entries_of_longer_limits() {
+        let store = MemoryRateLimitStore::new();
+        let hourly = RateLimit::per_hour(1);
+        let per_second = RateLimit::per_second(1);
+        let now = Instant::now();
+
+        assert!(store.check_at("hourly", &hourly, now).is_ok());
+        assert!(
+            store
+                .check_at("per_second", &per_second, now + CLEANUP_INTERVAL)
+                .is_ok()
+        );
+        assert!(store.check_at("hourly", &hourly, now + CLEANUP_INTERVAL).is_err());
+
+        assert!(store.check_at("other", &per_second, now + CLEANUP_INTERVAL * 3).is_ok());
+        assert!(!store.entries.lock().unwrap().contains_key("per_second"));
+        assert!(store.entries.lock().unwrap().contains_key("hourly"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Rate limit period must be greater than zero")]
+    fn zero_periods_are_rejected() {
+        RateLimit::new(10, Duration::ZERO);
+    }
+
+    #[test]
+    fn requests_without_client_address_are_rejected() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let service = RateLimitLayer::new(RateLimit::per_minute(10)).layer(tower::service_fn(|_: Request| async {
+            Ok::<_, std::convert::Infallible>(Response::new(Body::empty()))
+        }));
+
+        let response = runtime.block_on(service.oneshot(request(None, ""))).unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
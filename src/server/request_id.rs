@@ -0,0 +1,195 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::extract::Request;
+use axum::response::Response;
+use http::HeaderValue;
+use tower::{Layer, Service};
+use tracing::Span;
+use uuid::Uuid;
+
+use crate::constants::X_REQUEST_ID;
+
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        (!value.is_empty() && value.len() <= MAX_REQUEST_ID_LENGTH && value.chars().all(|char| char.is_ascii_graphic()))
+            .then(|| Self(value.to_owned()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for RequestId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|request_id| request_id.0.clone()).ok()
+}
+
+pub fn make_request_span<B>(request: &http::Request<B>) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(RequestId::as_str)
+        .unwrap_or_default();
+
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id,
+    )
+}
+
+#[derive(Clone, Default)]
+pub struct RequestIdLayer;
+
+impl RequestIdLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for RequestIdService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let request_id = request
+            .headers()
+            .get(X_REQUEST_ID)
+            .and_then(|value| value.to_str().ok())
+            .and_then(RequestId::parse)
+            .unwrap_or_default();
+        let header_value = HeaderValue::from_str(request_id.as_str()).expect("Could not parse request ID");
+
+        request.headers_mut().insert(X_REQUEST_ID, header_value.clone());
+        request.extensions_mut().insert(request_id.clone());
+
+        let future = CURRENT_REQUEST_ID.scope(request_id, self.inner.call(request));
+
+        Box::pin(async move {
+            let mut response = future.await?;
+
+            response.headers_mut().insert(X_REQUEST_ID, header_value);
+
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn call(request_id: Option<&str>) -> (Option<String>, Option<String>, Option<String>) {
+        let service = RequestIdLayer::new().layer(tower::service_fn(|request: Request| async move {
+            let mut response = Response::new(Body::empty());
+            let headers = response.headers_mut();
+
+            if let Some(request_id) = request.extensions().get::<RequestId>() {
+                headers.insert("x-extension", HeaderValue::from_str(request_id.as_str()).unwrap());
+            }
+
+            if let Some(request_id) = current_request_id() {
+                headers.insert("x-current", HeaderValue::from_str(&request_id).unwrap());
+            }
+
+            Ok::<_, std::convert::Infallible>(response)
+        }));
+        let mut request = Request::get("/").body(Body::empty()).unwrap();
+
+        if let Some(request_id) = request_id {
+            request
+                .headers_mut()
+                .insert(X_REQUEST_ID, HeaderValue::from_str(request_id).unwrap());
+        }
+
+        let response = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(service.oneshot(request))
+            .unwrap();
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .map(|value| value.to_str().unwrap().to_owned())
+        };
+
+        (
+            header(X_REQUEST_ID.as_str()),
+            header("x-extension"),
+            header("x-current"),
+        )
+    }
+
+    #[test]
+    fn valid_request_ids_are_accepted_and_echoed() {
+        let id = Some("client-request-1".to_owned());
+
+        assert_eq!(call(Some("client-request-1")), (id.clone(), id.clone(), id));
+    }
+
+    #[test]
+    fn missing_or_invalid_request_ids_are_generated() {
+        for request_id in [
+            None,
+            Some(""),
+            Some("has space"),
+            Some(&*"x".repeat(MAX_REQUEST_ID_LENGTH + 1)),
+        ] {
+            let (echoed, extension, current) = call(request_id);
+            let echoed = echoed.unwrap();
+
+            assert!(Uuid::try_parse(&echoed).is_ok(), "{echoed} is not generated");
+            assert_eq!(extension.as_ref(), Some(&echoed));
+            assert_eq!(current.as_ref(), Some(&echoed));
+        }
+
+        assert_ne!(call(None).0, call(None).0);
+    }
+}
@@ -0,0 +1,367 @@
+use std::fmt::{Display, Formatter};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use axum::extract::Request;
+use axum::response::Response;
+use http::header::{
+    CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+};
+use http::{HeaderName, HeaderValue};
+use tower::{Layer, Service};
+
+pub const CSP_DATA: &str = "data:";
+pub const CSP_NONE: &str = "'none'";
+pub const CSP_SELF: &str = "'self'";
+pub const CSP_UNSAFE_INLINE: &str = "'unsafe-inline'";
+pub const CSP_WASM_UNSAFE_EVAL: &str = "'wasm-unsafe-eval'";
+
+const CROSS_ORIGIN_OPENER_POLICY: HeaderName = HeaderName::from_static("cross-origin-opener-policy");
+const PERMISSIONS_POLICY: HeaderName = HeaderName::from_static("permissions-policy");
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContentSecurityPolicy {
+    directives: Vec<(String, Vec<String>)>,
+}
+
+impl ContentSecurityPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dioxus() -> Self {
+        let policy = Self::new()
+            .default_src(&[CSP_SELF])
+            .script_src(&[CSP_SELF, CSP_WASM_UNSAFE_EVAL, CSP_UNSAFE_INLINE])
+            .style_src(&[CSP_SELF, CSP_UNSAFE_INLINE])
+            .img_src(&[CSP_SELF, CSP_DATA])
+            .font_src(&[CSP_SELF, CSP_DATA])
+            .connect_src(&[CSP_SELF])
+            .object_src(&[CSP_NONE])
+            .base_uri(&[CSP_SELF])
+            .form_action(&[CSP_SELF])
+            .frame_ancestors(&[CSP_NONE]);
+
+        if cfg!(debug_assertions) {
+            policy.connect_src(&["ws:"])
+        } else {
+            policy
+        }
+    }
+
+    pub fn allow_inline_scripts(self) -> Self {
+        self.script_src(&[CSP_UNSAFE_INLINE])
+    }
+
+    pub fn directive(mut self, name: &str, sources: &[&str]) -> Self {
+        let sources = sources.iter().map(|source| (*source).to_owned());
+
+        match self.directives.iter_mut().find(|(current, _)| current == name) {
+            Some((_, current_sources)) => {
+                for source in sources {
+                    if !current_sources.contains(&source) {
+                        current_sources.push(source);
+                    }
+                }
+            }
+            None => self.directives.push((name.to_owned(), sources.collect())),
+        }
+
+        self
+    }
+
+    pub fn remove_directive(mut self, name: &str) -> Self {
+        self.directives.retain(|(current, _)| current != name);
+        self
+    }
+
+    pub fn base_uri(self, sources: &[&str]) -> Self {
+        self.directive("base-uri", sources)
+    }
+
+    pub fn connect_src(self, sources: &[&str]) -> Self {
+        self.directive("connect-src", sources)
+    }
+
+    pub fn default_src(self, sources: &[&str]) -> Self {
+        self.directive("default-src", sources)
+    }
+
+    pub fn font_src(self, sources: &[&str]) -> Self {
+        self.directive("font-src", sources)
+    }
+
+    pub fn form_action(self, sources: &[&str]) -> Self {
+        self.directive("form-action", sources)
+    }
+
+    pub fn frame_ancestors(self, sources: &[&str]) -> Self {
+        self.directive("frame-ancestors", sources)
+    }
+
+    pub fn frame_src(self, sources: &[&str]) -> Self {
+        self.directive("frame-src", sources)
+    }
+
+    pub fn img_src(self, sources: &[&str]) -> Self {
+        self.directive("img-src", sources)
+    }
+
+    pub fn media_src(self, sources: &[&str]) -> Self {
+        self.directive("media-src", sources)
+    }
+
+    pub fn object_src(self, sources: &[&str]) -> Self {
+        self.directive("object-src", sources)
+    }
+
+    pub fn script_src(self, sources: &[&str]) -> Self {
+        self.directive("script-src", sources)
+    }
+
+    pub fn style_src(self, sources: &[&str]) -> Self {
+        self.directive("style-src", sources)
+    }
+
+    pub fn upgrade_insecure_requests(self) -> Self {
+        self.directive("upgrade-insecure-requests", &[])
+    }
+
+    pub fn to_header_value(&self) -> HeaderValue {
+        HeaderValue::from_str(&self.to_string()).expect("Could not parse Content-Security-Policy")
+    }
+}
+
+impl Display for ContentSecurityPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let directives = self
+            .directives
+            .iter()
+            .map(|(name, sources)| {
+                if sources.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{name} {}", sources.join(" "))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", directives.join("; "))
+    }
+}
+
+#[derive(Clone)]
+pub struct SecurityHeadersLayer {
+    headers: Arc<Vec<(HeaderName, HeaderValue)>>,
+}
+
+impl Default for SecurityHeadersLayer {
+    fn default() -> Self {
+        Self {
+            headers: Arc::new(Vec::new()),
+        }
+        .hsts(Some(Duration::from_secs(31_536_000)))
+        .content_security_policy(Some(ContentSecurityPolicy::dioxus()))
+        .header(X_FRAME_OPTIONS, HeaderValue::from_static("DENY"))
+        .header(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"))
+        .header(
+            REFERRER_POLICY,
+            HeaderValue::from_static("strict-origin-when-cross-origin"),
+        )
+        .header(
+            PERMISSIONS_POLICY,
+            HeaderValue::from_static("camera=(), geolocation=(), microphone=()"),
+        )
+        .header(CROSS_ORIGIN_OPENER_POLICY, HeaderValue::from_static("same-origin"))
+    }
+}
+
+impl SecurityHeadersLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        let headers = Arc::make_mut(&mut self.headers);
+
+        headers.retain(|(current, _)| current != name);
+        headers.push((name, value));
+
+        self
+    }
+
+    pub fn remove_header(mut self, name: HeaderName) -> Self {
+        Arc::make_mut(&mut self.headers).retain(|(current, _)| current != name);
+        self
+    }
+
+    pub fn content_security_policy(self, policy: Option<ContentSecurityPolicy>) -> Self {
+        match policy {
+            Some(policy) => self.header(CONTENT_SECURITY_POLICY, policy.to_header_value()),
+            None => self.remove_header(CONTENT_SECURITY_POLICY),
+        }
+    }
+
+    pub fn hsts(self, max_age: Option<Duration>) -> Self {
+        match max_age {
+            Some(max_age) => self.header(
+                STRICT_TRANSPORT_SECURITY,
+                HeaderValue::from_str(&format!("max-age={}", max_age.as_secs()))
+                    .expect("Could not parse Strict-Transport-Security"),
+            ),
+            None => self.remove_header(STRICT_TRANSPORT_SECURITY),
+        }
+    }
+
+    pub fn hsts_include_subdomains(self, max_age: Duration) -> Self {
+        self.header(
+            STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_str(&format!("max-age={}; includeSubDomains", max_age.as_secs()))
+                .expect("Could not parse Strict-Transport-Security"),
+        )
+    }
+
+    pub fn frame_options(self, value: &'static str) -> Self {
+        self.header(X_FRAME_OPTIONS, HeaderValue::from_static(value))
+    }
+
+    pub fn referrer_policy(self, value: &'static str) -> Self {
+        self.header(REFERRER_POLICY, HeaderValue::from_static(value))
+    }
+}
+
+impl<S> Layer<S> for SecurityHeadersLayer {
+    type Service = SecurityHeadersService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SecurityHeadersService {
+            inner,
+            headers: self.headers.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SecurityHeadersService<S> {
+    inner: S,
+    headers: Arc<Vec<(HeaderName, HeaderValue)>>,
+}
+
+impl<S> Service<Request> for SecurityHeadersService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let headers = self.headers.clone();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let mut response = future.await?;
+
+            for (name, value) in headers.iter() {
+                if !response.headers().contains_key(name) {
+                    response.headers_mut().insert(name.clone(), value.clone());
+                }
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_value(layer: &SecurityHeadersLayer, name: &HeaderName) -> Option<String> {
+        layer
+            .headers
+            .iter()
+            .find(|(current, _)| current == name)
+            .map(|(_, value)| value.to_str().unwrap().to_owned())
+    }
+
+    #[test]
+    fn defaults_do_not_include_subdomains_but_allow_dioxus_inline_scripts() {
+        let layer = SecurityHeadersLayer::default();
+        let policy = header_value(&layer, &CONTENT_SECURITY_POLICY).unwrap();
+
+        assert_eq!(
+            header_value(&layer, &STRICT_TRANSPORT_SECURITY).as_deref(),
+            Some("max-age=31536000")
+        );
+        assert!(policy.contains("script-src 'self' 'wasm-unsafe-eval' 'unsafe-inline';"));
+    }
+
+    #[test]
+    fn subdomains_are_opt_in() {
+        let layer = SecurityHeadersLayer::default().hsts_include_subdomains(Duration::from_secs(60));
+
+        assert_eq!(
+            header_value(&layer, &STRICT_TRANSPORT_SECURITY).as_deref(),
+            Some("max-age=60; includeSubDomains")
+        );
+    }
+
+    #[test]
+    fn inline_scripts_can_be_removed_from_the_policy() {
+        let policy = ContentSecurityPolicy::dioxus()
+            .remove_directive("script-src")
+            .script_src(&[CSP_SELF, CSP_WASM_UNSAFE_EVAL]);
+
+        assert!(policy.to_string().ends_with("; script-src 'self' 'wasm-unsafe-eval'"));
+        assert_eq!(
+            ContentSecurityPolicy::dioxus().allow_inline_scripts(),
+            ContentSecurityPolicy::dioxus()
+        );
+    }
+
+    #[cfg(feature = "app")]
+    #[test]
+    fn dioxus_ssr_responses_keep_their_inline_scripts_allowed() {
+        use axum::Router;
+        use axum::body::{Body, to_bytes};
+        use axum::routing::get;
+        use dioxus::prelude::*;
+        use dioxus::server::{FullstackState, ServeConfig, render_handler};
+        use tower::ServiceExt;
+
+        fn app() -> Element {
+            rsx! { h1 { "Hello" } }
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let router = Router::new()
+                .fallback(get(render_handler))
+                .with_state(FullstackState::new(ServeConfig::new(), app))
+                .layer(SecurityHeadersLayer::default());
+            let response = router
+                .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let policy = response.headers()[CONTENT_SECURITY_POLICY].to_str().unwrap().to_owned();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body = String::from_utf8_lossy(&body);
+
+            assert!(body.contains("Hello"));
+            assert!(body.contains("<script>window.initial_dioxus_hydration_data="));
+            assert!(policy.contains(CSP_UNSAFE_INLINE));
+        });
+    }
+}
@@ -0,0 +1,154 @@
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use super::shutdown_signal;
+
+#[derive(Clone)]
+pub struct ShutdownSignal {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(false)),
+        }
+    }
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_os_signals() -> Self {
+        let signal = Self::new();
+
+        signal.listen_os_signals();
+
+        signal
+    }
+
+    pub fn listen_os_signals(&self) {
+        let signal = self.clone();
+
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            signal.trigger();
+        });
+    }
+
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    pub async fn triggered(&self) {
+        let mut receiver = self.sender.subscribe();
+
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+pub struct ShutdownOutcome {
+    pub server: std::io::Result<()>,
+    #[cfg(feature = "monitor")]
+    pub monitor: std::io::Result<()>,
+}
+
+impl ShutdownOutcome {
+    pub fn is_ok(&self) -> bool {
+        #[cfg(feature = "monitor")]
+        if self.monitor.is_err() {
+            return false;
+        }
+
+        self.server.is_ok()
+    }
+
+    pub fn into_result(self) -> std::io::Result<()> {
+        self.server?;
+
+        #[cfg(feature = "monitor")]
+        self.monitor?;
+
+        Ok(())
+    }
+}
+
+pub(crate) async fn drain_with_timeout(
+    name: &str,
+    future: impl Future<Output = std::io::Result<()>>,
+    signal: &ShutdownSignal,
+    timeout: Duration,
+) -> std::io::Result<()> {
+    let mut future = std::pin::pin!(future);
+
+    tokio::select! {
+        biased;
+
+        result = &mut future => return result,
+        _ = signal.triggered() => {}
+    }
+
+    info!("Waiting up to {} ms for {name} to drain", timeout.as_millis());
+
+    tokio::time::timeout(timeout, future).await.unwrap_or_else(|_| {
+        warn!("The {name} did not drain within {} ms", timeout.as_millis());
+
+        Err(Error::new(
+            ErrorKind::TimedOut,
+            format!("The {name} did not drain in time"),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run<T>(future: impl Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn drain_times_out_after_the_signal() {
+        let signal = ShutdownSignal::new();
+
+        signal.trigger();
+
+        let result = run(drain_with_timeout(
+            "test",
+            std::future::pending(),
+            &signal,
+            Duration::from_millis(10),
+        ));
+
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn drain_returns_the_future_result() {
+        let signal = ShutdownSignal::new();
+
+        let result = run(drain_with_timeout(
+            "test",
+            async { Err(Error::other("failed")) },
+            &signal,
+            Duration::from_millis(10),
+        ));
+
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::Other);
+    }
+}
@@ -0,0 +1,172 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::body::Body;
+use axum::extract::Request;
+use axum::response::Response;
+use http::header::{CACHE_CONTROL, CONTENT_LENGTH, ETAG, IF_NONE_MATCH, LAST_MODIFIED};
+use http::{HeaderValue, Method, StatusCode};
+use tower::{Layer, Service};
+use tower_http::services::ServeDir;
+
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+const REVALIDATE_CACHE_CONTROL: &str = "no-cache";
+
+pub fn static_dir(path: impl AsRef<Path>) -> ServeDir {
+    ServeDir::new(path).precompressed_br().precompressed_gzip()
+}
+
+pub fn is_hashed_asset(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let Some((stem, extension)) = file_name.split_once('.') else {
+        return false;
+    };
+    if uuid::Uuid::try_parse(stem).is_ok() {
+        return false;
+    }
+
+    let Some((name, hash)) = stem.rsplit_once(['-', '_']) else {
+        return false;
+    };
+    let hash = hash.strip_prefix("dxh").unwrap_or(hash);
+
+    !name.is_empty()
+        && !extension.is_empty()
+        && (8..=64).contains(&hash.len())
+        && hash
+            .chars()
+            .all(|char| char.is_ascii_digit() || ('a'..='f').contains(&char))
+}
+
+#[derive(Clone, Default)]
+pub struct StaticCacheLayer;
+
+impl StaticCacheLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for StaticCacheLayer {
+    type Service = StaticCacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        StaticCacheService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct StaticCacheService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for StaticCacheService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let is_cacheable = matches!(*request.method(), Method::GET | Method::HEAD);
+        let is_hashed = is_hashed_asset(request.uri().path());
+        let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let mut response = future.await?;
+
+            if !is_cacheable || response.status() != StatusCode::OK {
+                return Ok(response);
+            }
+
+            if is_hashed {
+                response
+                    .headers_mut()
+                    .entry(CACHE_CONTROL)
+                    .or_insert(HeaderValue::from_static(IMMUTABLE_CACHE_CONTROL));
+
+                return Ok(response);
+            }
+
+            let Some(last_modified) = response.headers().get(LAST_MODIFIED) else {
+                return Ok(response);
+            };
+
+            let etag = match response.headers().get(ETAG) {
+                Some(etag) => etag.clone(),
+                None => {
+                    let mut hasher = DefaultHasher::new();
+
+                    last_modified.as_bytes().hash(&mut hasher);
+                    response
+                        .headers()
+                        .get(CONTENT_LENGTH)
+                        .map(HeaderValue::as_bytes)
+                        .hash(&mut hasher);
+
+                    HeaderValue::from_str(&format!("W/\"{:016x}\"", hasher.finish())).expect("Could not parse ETag")
+                }
+            };
+
+            if if_none_match.is_some_and(|if_none_match| etag_matches(&if_none_match, &etag)) {
+                let mut not_modified = Response::new(Body::empty());
+
+                *not_modified.status_mut() = StatusCode::NOT_MODIFIED;
+                not_modified.headers_mut().insert(ETAG, etag);
+
+                for name in [CACHE_CONTROL, LAST_MODIFIED] {
+                    if let Some(value) = response.headers().get(&name) {
+                        not_modified.headers_mut().insert(name, value.clone());
+                    }
+                }
+
+                return Ok(not_modified);
+            }
+
+            response.headers_mut().insert(ETAG, etag);
+            response
+                .headers_mut()
+                .entry(CACHE_CONTROL)
+                .or_insert(HeaderValue::from_static(REVALIDATE_CACHE_CONTROL));
+
+            Ok(response)
+        })
+    }
+}
+
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(if_none_match) = if_none_match.to_str() else {
+        return false;
+    };
+    let etag = etag.to_str().unwrap_or_default().trim_start_matches("W/");
+
+    if_none_match
+        .split(',')
+        .map(|value| value.trim())
+        .any(|value| value == "*" || value.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashed_assets_need_a_hash_and_an_extension() {
+        assert!(is_hashed_asset("/assets/main-dxh0123abcd4567.js"));
+        assert!(is_hashed_asset("/assets/style-3f2a9c1b.css"));
+        assert!(!is_hashed_asset("/api/users/123e4567-e89b-12d3-a456-426614174000"));
+        assert!(!is_hashed_asset("/api/users/123e4567-e89b-12d3-a456-426614174000.json"));
+        assert!(!is_hashed_asset("/api/tokens/session_deadbeefcafe"));
+        assert!(!is_hashed_asset("/assets/main.js"));
+        assert!(!is_hashed_asset("/assets/-deadbeef.js"));
+    }
+}
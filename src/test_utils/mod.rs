@@ -16,6 +16,12 @@ use uuid::Uuid;
 use crate::core::generate_random_string;
 use crate::core::identity_client::Auth;
 
+#[cfg(feature = "monitor")]
+mod monitor;
+
+#[cfg(feature = "monitor")]
+pub use monitor::*;
+
 fn unique_fake<T, F>(prefix: &str, fake_fn: F) -> T
 where
     F: Fn() -> T,
@@ -0,0 +1,505 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Display;
+use std::pin::{Pin, pin};
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+
+use apalis::prelude::{
+    Backend, BoxDynError, Context, Error, Event, Monitor, Poller, Request, ServiceFn, TaskId, Worker,
+};
+use apalis_core::codec::NoopCodec;
+use futures_util::StreamExt;
+use futures_util::future::select_all;
+use futures_util::stream::{self, BoxStream};
+use tokio::sync::Notify;
+use tower::{Layer, Service};
+
+use crate::core::config::MonitorConfig;
+use crate::monitor::{
+    EventHooks, Job, JobOptions, JobWorkerService, MonitorExt, MonitorJobExt, RetryBackend, RetryFuture, track_queued,
+};
+
+const DEFAULT_RUN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug)]
+pub struct JobOutcome<J> {
+    pub task_id: String,
+    pub job: J,
+    pub attempts: usize,
+    pub error: Option<String>,
+}
+
+impl<J> JobOutcome<J> {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+struct TestQueue<J> {
+    state: Mutex<TestQueueState<J>>,
+    changed: Notify,
+}
+
+struct TestQueueState<J> {
+    pending: VecDeque<Request<J, ()>>,
+    in_flight: usize,
+    idle: bool,
+    retrying: HashSet<String>,
+    retry_delays: Vec<Duration>,
+    outcomes: Vec<JobOutcome<J>>,
+    workers: Vec<Worker<Context>>,
+}
+
+impl<J> TestQueue<J> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, TestQueueState<J>> {
+        self.state.lock().expect("Could not lock test backend")
+    }
+
+    fn finish(&self, outcome: JobOutcome<J>) {
+        let mut state = self.lock();
+
+        state.in_flight = state.in_flight.saturating_sub(1);
+
+        if !state.retrying.remove(&outcome.task_id) {
+            state.outcomes.push(outcome);
+        }
+
+        drop(state);
+        self.changed.notify_waiters();
+    }
+}
+
+trait IdleCheck: Send + Sync {
+    fn is_idle(&self) -> bool;
+
+    fn changed(&self) -> &Notify;
+
+    fn stop(&self);
+}
+
+impl<J: Send> IdleCheck for TestQueue<J> {
+    fn is_idle(&self) -> bool {
+        let state = self.lock();
+
+        state.pending.is_empty() && state.in_flight == 0 && state.idle
+    }
+
+    fn changed(&self) -> &Notify {
+        &self.changed
+    }
+
+    fn stop(&self) {
+        for worker in &self.lock().workers {
+            worker.stop();
+        }
+    }
+}
+
+pub struct TestBackend<J> {
+    queue: Arc<TestQueue<J>>,
+}
+
+impl<J> Clone for TestBackend<J> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<J> Default for TestBackend<J> {
+    fn default() -> Self {
+        Self {
+            queue: Arc::new(TestQueue {
+                state: Mutex::new(TestQueueState {
+                    pending: VecDeque::new(),
+                    in_flight: 0,
+                    idle: false,
+                    retrying: HashSet::new(),
+                    retry_delays: Vec::new(),
+                    outcomes: Vec::new(),
+                    workers: Vec::new(),
+                }),
+                changed: Notify::new(),
+            }),
+        }
+    }
+}
+
+impl<J: Clone> TestBackend<J> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enqueue(&self, job: J) -> TaskId
+    where
+        J: Job,
+    {
+        self.enqueue_request(Request::new(job))
+    }
+
+    pub fn enqueue_request(&self, request: Request<J, ()>) -> TaskId
+    where
+        J: Job,
+    {
+        let task_id = request.parts.task_id.clone();
+
+        track_queued(&task_id, J::NAME);
+
+        let mut state = self.queue.lock();
+
+        state.pending.push_back(request);
+        state.idle = false;
+
+        drop(state);
+        self.queue.changed.notify_waiters();
+
+        task_id
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.lock().pending.len()
+    }
+
+    pub fn retry_delays(&self) -> Vec<Duration> {
+        self.queue.lock().retry_delays.clone()
+    }
+
+    pub fn outcomes(&self) -> Vec<JobOutcome<J>> {
+        self.queue.lock().outcomes.clone()
+    }
+
+    pub fn outcome(&self, task_id: &TaskId) -> Option<JobOutcome<J>> {
+        let task_id = task_id.to_string();
+
+        self.queue
+            .lock()
+            .outcomes
+            .iter()
+            .find(|outcome| outcome.task_id == task_id)
+            .cloned()
+    }
+
+    pub fn completed(&self) -> Vec<JobOutcome<J>> {
+        self.outcomes().into_iter().filter(JobOutcome::is_success).collect()
+    }
+
+    pub fn failed(&self) -> Vec<JobOutcome<J>> {
+        self.outcomes()
+            .into_iter()
+            .filter(|outcome| !outcome.is_success())
+            .collect()
+    }
+}
+
+impl<J: Send + 'static> RetryBackend<J, ()> for TestBackend<J> {
+    fn retry(&self, request: Request<J, ()>, delay: Duration) -> RetryFuture {
+        let mut state = self.queue.lock();
+
+        state.retrying.insert(request.parts.task_id.to_string());
+        state.retry_delays.push(delay);
+        state.pending.push_back(request);
+        state.idle = false;
+
+        drop(state);
+        self.queue.changed.notify_waiters();
+
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl<J: Clone + Send + Sync + 'static> Backend<Request<J, ()>> for TestBackend<J> {
+    type Stream = BoxStream<'static, Result<Option<Request<J, ()>>, Error>>;
+
+    type Layer = TestBackendLayer<J>;
+
+    type Codec = NoopCodec<Request<J, ()>>;
+
+    fn poll(self, worker: &Worker<Context>) -> Poller<Self::Stream, Self::Layer> {
+        let worker = worker.clone();
+        let queue = self.queue.clone();
+
+        queue.lock().workers.push(worker.clone());
+
+        let stream = stream::unfold((), move |_| {
+            let worker = worker.clone();
+            let queue = queue.clone();
+
+            async move {
+                loop {
+                    if worker.is_shutting_down() {
+                        return None;
+                    }
+
+                    let mut changed = pin!(queue.changed.notified());
+
+                    changed.as_mut().enable();
+
+                    {
+                        let mut state = queue.lock();
+
+                        if let Some(request) = state.pending.pop_front() {
+                            state.in_flight += 1;
+
+                            return Some((Ok(Some(request)), ()));
+                        }
+
+                        if !state.idle && state.in_flight == 0 {
+                            state.idle = true;
+
+                            drop(state);
+                            queue.changed.notify_waiters();
+
+                            return Some((Ok(None), ()));
+                        }
+                    }
+
+                    changed.await;
+                }
+            }
+        });
+
+        Poller::new_with_layer(
+            stream.boxed(),
+            std::future::pending(),
+            TestBackendLayer { queue: self.queue },
+        )
+    }
+}
+
+pub struct TestBackendLayer<J> {
+    queue: Arc<TestQueue<J>>,
+}
+
+impl<S, J> Layer<S> for TestBackendLayer<J> {
+    type Service = TestBackendService<S, J>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TestBackendService {
+            inner,
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+pub struct TestBackendService<S, J> {
+    inner: S,
+    queue: Arc<TestQueue<J>>,
+}
+
+impl<S, J> Service<Request<J, ()>> for TestBackendService<S, J>
+where
+    S: Service<Request<J, ()>>,
+    S::Future: Send + 'static,
+    S::Error: Display,
+    J: Clone + Send + Sync + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<J, ()>) -> Self::Future {
+        let queue = self.queue.clone();
+        let task_id = request.parts.task_id.to_string();
+        let attempt = request.parts.attempt.clone();
+        let job = request.args.clone();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let result = future.await;
+
+            queue.finish(JobOutcome {
+                task_id,
+                job,
+                attempts: attempt.current(),
+                error: result.as_ref().err().map(ToString::to_string),
+            });
+
+            result
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WorkerEvent {
+    Start,
+    Engage(String),
+    Idle,
+    Custom(String),
+    Error(String),
+    Stop,
+    Exit,
+}
+
+impl From<&Event> for WorkerEvent {
+    fn from(event: &Event) -> Self {
+        match event {
+            Event::Start => Self::Start,
+            Event::Engage(task_id) => Self::Engage(task_id.to_string()),
+            Event::Idle => Self::Idle,
+            Event::Custom(message) => Self::Custom(message.clone()),
+            Event::Error(error) => Self::Error(error.to_string()),
+            Event::Stop => Self::Stop,
+            Event::Exit => Self::Exit,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordedEvent {
+    pub worker: String,
+    pub event: WorkerEvent,
+}
+
+#[derive(Clone, Debug)]
+pub struct JobRun {
+    pub events: Vec<RecordedEvent>,
+}
+
+impl JobRun {
+    pub fn worker_events(&self, worker: &str) -> Vec<WorkerEvent> {
+        self.events
+            .iter()
+            .filter(|recorded| recorded.worker == worker)
+            .map(|recorded| recorded.event.clone())
+            .collect()
+    }
+
+    pub fn has_event(&self, worker: &str, event: &WorkerEvent) -> bool {
+        self.events
+            .iter()
+            .any(|recorded| recorded.worker == worker && &recorded.event == event)
+    }
+}
+
+pub struct JobHarness {
+    monitor: Monitor,
+    events: Arc<Mutex<Vec<RecordedEvent>>>,
+    queues: Vec<Arc<dyn IdleCheck>>,
+    timeout: Duration,
+}
+
+impl Default for JobHarness {
+    fn default() -> Self {
+        Self::with_config(&MonitorConfig::default())
+    }
+}
+
+impl JobHarness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: &MonitorConfig) -> Self {
+        Self::with_hooks(config, EventHooks::new())
+    }
+
+    pub fn with_hooks(config: &MonitorConfig, hooks: EventHooks) -> Self {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorder = events.clone();
+        let hooks = hooks.on_event(move |e: &Worker<Event>| {
+            if let Ok(mut events) = recorder.lock() {
+                events.push(RecordedEvent {
+                    worker: e.id().name().to_owned(),
+                    event: e.inner().into(),
+                });
+            }
+        });
+
+        Self {
+            monitor: Monitor::setup_with_hooks(config, hooks),
+            events,
+            queues: Vec::new(),
+            timeout: DEFAULT_RUN_TIMEOUT,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn register_job<J, F, Args>(self, backend: &TestBackend<J>, handler: F) -> Self
+    where
+        J: Job,
+        ServiceFn<F, J, (), Args>: Service<Request<J, ()>, Error = Error> + Clone + Send + 'static,
+        <ServiceFn<F, J, (), Args> as Service<Request<J, ()>>>::Future: Send + 'static,
+        <ServiceFn<F, J, (), Args> as Service<Request<J, ()>>>::Response: Send + 'static,
+        TestBackendService<JobWorkerService<F, J, (), Args>, J>: Service<Request<J, ()>> + Send,
+        <TestBackendService<JobWorkerService<F, J, (), Args>, J> as Service<Request<J, ()>>>::Future: Send,
+        <TestBackendService<JobWorkerService<F, J, (), Args>, J> as Service<Request<J, ()>>>::Error:
+            Send + Sync + Into<BoxDynError>,
+    {
+        self.register_job_with(backend, handler, J::options())
+    }
+
+    pub fn register_job_with<J, F, Args>(mut self, backend: &TestBackend<J>, handler: F, options: JobOptions) -> Self
+    where
+        J: Job,
+        ServiceFn<F, J, (), Args>: Service<Request<J, ()>, Error = Error> + Clone + Send + 'static,
+        <ServiceFn<F, J, (), Args> as Service<Request<J, ()>>>::Future: Send + 'static,
+        <ServiceFn<F, J, (), Args> as Service<Request<J, ()>>>::Response: Send + 'static,
+        TestBackendService<JobWorkerService<F, J, (), Args>, J>: Service<Request<J, ()>> + Send,
+        <TestBackendService<JobWorkerService<F, J, (), Args>, J> as Service<Request<J, ()>>>::Future: Send,
+        <TestBackendService<JobWorkerService<F, J, (), Args>, J> as Service<Request<J, ()>>>::Error:
+            Send + Sync + Into<BoxDynError>,
+    {
+        self.queues.push(backend.queue.clone());
+        self.monitor = self.monitor.register_job_with(backend.clone(), handler, options);
+        self
+    }
+
+    pub async fn run_until_idle(self) -> JobRun {
+        let queues = self.queues;
+        let timeout = self.timeout;
+
+        let signal = async move {
+            let result = tokio::time::timeout(timeout, wait_until_idle(&queues)).await;
+
+            for queue in &queues {
+                queue.stop();
+            }
+
+            assert!(
+                result.is_ok(),
+                "Jobs did not become idle within {} ms",
+                timeout.as_millis()
+            );
+
+            Ok(())
+        };
+
+        self.monitor
+            .run_with_signal(signal)
+            .await
+            .expect("Could not run monitor");
+
+        JobRun {
+            events: self.events.lock().map(|events| events.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+async fn wait_until_idle(queues: &[Arc<dyn IdleCheck>]) {
+    loop {
+        let mut changes = queues
+            .iter()
+            .map(|queue| Box::pin(queue.changed().notified()))
+            .collect::<Vec<_>>();
+
+        for changed in &mut changes {
+            changed.as_mut().enable();
+        }
+
+        if changes.is_empty() || queues.iter().all(|queue| queue.is_idle()) {
+            return;
+        }
+
+        select_all(changes).await;
+    }
+}
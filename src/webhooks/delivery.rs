@@ -0,0 +1,107 @@
+use std::time::Instant;
+
+use apalis::prelude::{Attempt, Error};
+use chrono::Utc;
+use http::header::CONTENT_TYPE;
+
+use crate::constants::X_WEBHOOK_SIGNATURE;
+use crate::monitor::{Job, JobError, JobResultExt};
+
+#[cfg(feature = "metrics")]
+use crate::metrics::Counter;
+
+use super::{WebhookDelivery, WebhookDeliveryLog, WebhookStore, sign_webhook_payload, webhook_client, webhook_store};
+
+#[cfg(feature = "metrics")]
+const WEBHOOK_DELIVERIES: Counter = Counter::new(
+    "webhook_delivery_attempts_total",
+    "Total number of outbound webhook delivery attempts.",
+);
+
+pub async fn deliver_webhook(delivery: WebhookDelivery, attempt: Attempt) -> Result<(), Error> {
+    let store = webhook_store();
+    let Some(endpoint) = store.endpoint(&delivery.endpoint_id).retryable()? else {
+        return Err(JobError::permanent(format!("Webhook endpoint {} does not exist", delivery.endpoint_id)).into());
+    };
+
+    if !endpoint.enabled {
+        return Err(JobError::permanent(format!("Webhook endpoint {} is disabled", endpoint.id)).into());
+    }
+
+    let body = serde_json::to_vec(&serde_json::json!({
+        "id": delivery.id,
+        "event_type": delivery.event_type,
+        "created_at": delivery.created_at,
+        "data": delivery.payload,
+    }))
+    .permanent()?;
+    let signature = sign_webhook_payload(&endpoint.secret, &body);
+    let started_at = Instant::now();
+
+    let result = send_webhook(&endpoint.url, signature, body).await;
+
+    let (status, error) = match &result {
+        Ok(status) => (Some(*status), None),
+        Err((status, error)) => (*status, Some(error)),
+    };
+    let finished =
+        error.is_none_or(|error| error.is_permanent() || attempt.current() > WebhookDelivery::options().retries);
+
+    record_delivery_log(
+        store.as_ref(),
+        WebhookDeliveryLog {
+            delivery_id: delivery.id.clone(),
+            endpoint_id: endpoint.id,
+            event_type: delivery.event_type.clone(),
+            attempt: attempt.current(),
+            status,
+            error: error.map(ToString::to_string),
+            duration_ms: started_at.elapsed().as_millis() as u64,
+            delivered_at: Utc::now(),
+        },
+        finished,
+    );
+
+    result.map(|_| ()).map_err(|(_, error)| error.into())
+}
+
+async fn send_webhook(url: &str, signature: String, body: Vec<u8>) -> Result<u16, (Option<u16>, JobError)> {
+    let (client, url) = webhook_client(url).await.map_err(|error| (None, error.into()))?;
+
+    let response = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(X_WEBHOOK_SIGNATURE, signature)
+        .body(body)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|error| (error.status().map(|status| status.as_u16()), error.into()))?;
+
+    let status = response.status();
+
+    if !status.is_success() {
+        return Err((
+            Some(status.as_u16()),
+            JobError::permanent(format!("Webhook endpoint responded with {status}")),
+        ));
+    }
+
+    Ok(status.as_u16())
+}
+
+fn record_delivery_log(store: &dyn WebhookStore, log: WebhookDeliveryLog, finished: bool) {
+    #[cfg(feature = "metrics")]
+    WEBHOOK_DELIVERIES.increment(&[
+        ("event_type", &log.event_type),
+        ("outcome", if log.is_success() { "success" } else { "failure" }),
+    ]);
+
+    if finished && let Err(err) = store.record_result(&log.endpoint_id, log.is_success()) {
+        tracing::error!("Could not record webhook endpoint result: {err}");
+    }
+
+    if let Err(err) = store.push_delivery_log(log) {
+        tracing::error!("Could not store webhook delivery log: {err}");
+    }
+}
@@ -0,0 +1,312 @@
+use std::fmt;
+use std::time::Duration;
+
+use apalis::prelude::{BoxDynError, Storage, TaskId};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::monitor::{Job, JobOptions, JobStorageExt};
+
+mod delivery;
+mod store;
+mod target;
+
+pub use delivery::*;
+pub use store::*;
+pub use target::*;
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct WebhookEndpoint {
+    pub id: String,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<String>,
+    pub enabled: bool,
+    pub consecutive_failures: usize,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl fmt::Debug for WebhookEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookEndpoint")
+            .field("id", &self.id)
+            .field("url", &self.url)
+            .field("secret", &"********")
+            .field("event_types", &self.event_types)
+            .field("enabled", &self.enabled)
+            .field("consecutive_failures", &self.consecutive_failures)
+            .field("disabled_at", &self.disabled_at)
+            .field("created_at", &self.created_at)
+            .finish()
+    }
+}
+
+impl WebhookEndpoint {
+    pub fn new(url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            url: url.into(),
+            secret: secret.into(),
+            event_types: Vec::new(),
+            enabled: true,
+            consecutive_failures: 0,
+            disabled_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn event_types<T: Into<String>>(mut self, event_types: impl IntoIterator<Item = T>) -> Self {
+        self.event_types = event_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn accepts(&self, event_type: &str) -> bool {
+        self.enabled && (self.event_types.is_empty() || self.event_types.iter().any(|accepted| accepted == event_type))
+    }
+
+    pub fn record_result(&mut self, success: bool, disable_after: usize) {
+        if success {
+            self.consecutive_failures = 0;
+            return;
+        }
+
+        self.consecutive_failures += 1;
+
+        if self.enabled && disable_after > 0 && self.consecutive_failures >= disable_after {
+            self.enabled = false;
+            self.disabled_at = Some(Utc::now());
+
+            tracing::warn!(
+                "Webhook endpoint {} was disabled after {} consecutive failures",
+                self.id,
+                self.consecutive_failures
+            );
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub endpoint_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Job for WebhookDelivery {
+    const NAME: &'static str = "webhook";
+
+    fn options() -> JobOptions {
+        JobOptions::new()
+            .retries(5)
+            .backoff(Duration::from_secs(10), Duration::from_secs(3600))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebhookDeliveryLog {
+    pub delivery_id: String,
+    pub endpoint_id: String,
+    pub event_type: String,
+    pub attempt: usize,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub delivered_at: DateTime<Utc>,
+}
+
+impl WebhookDeliveryLog {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+pub fn sign_webhook_payload(secret: &str, body: &[u8]) -> String {
+    let mut hmac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+
+    hmac.update(body);
+
+    base64::engine::general_purpose::STANDARD.encode(hmac.finalize().into_bytes())
+}
+
+pub fn register_webhook_endpoint(endpoint: WebhookEndpoint) -> Result<WebhookEndpoint, BoxDynError> {
+    validate_webhook_url(&endpoint.url)?;
+    webhook_store().save_endpoint(endpoint.clone())?;
+
+    Ok(endpoint)
+}
+
+pub fn enable_webhook_endpoint(id: &str) -> Result<bool, BoxDynError> {
+    let store = webhook_store();
+    let Some(mut endpoint) = store.endpoint(id)? else {
+        return Ok(false);
+    };
+
+    endpoint.enabled = true;
+    endpoint.consecutive_failures = 0;
+    endpoint.disabled_at = None;
+
+    store.save_endpoint(endpoint)?;
+
+    Ok(true)
+}
+
+pub async fn dispatch_webhook<S>(
+    storage: &mut S,
+    event_type: &str,
+    payload: &impl Serialize,
+) -> Result<Vec<TaskId>, BoxDynError>
+where
+    S: Storage<Job = WebhookDelivery> + Send,
+    S::Context: Send,
+    S::Error: Into<BoxDynError>,
+{
+    let payload = serde_json::to_value(payload)?;
+    let mut task_ids = Vec::new();
+
+    for endpoint in webhook_store().endpoints()? {
+        if !endpoint.accepts(event_type) {
+            continue;
+        }
+
+        let delivery = WebhookDelivery {
+            id: Uuid::new_v4().to_string(),
+            endpoint_id: endpoint.id,
+            event_type: event_type.to_owned(),
+            payload: payload.clone(),
+            created_at: Utc::now(),
+        };
+
+        let parts = storage.push_job(delivery).await.map_err(Into::into)?;
+
+        task_ids.push(parts.task_id);
+    }
+
+    Ok(task_ids)
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use crate::core::config::WEBHOOK_CONFIG;
+    use crate::test_utils::{JobHarness, TestBackend, WorkerEvent};
+
+    use super::*;
+
+    fn delivery(endpoint_id: &str) -> WebhookDelivery {
+        WebhookDelivery {
+            id: Uuid::new_v4().to_string(),
+            endpoint_id: endpoint_id.to_owned(),
+            event_type: "user.created".to_owned(),
+            payload: serde_json::json!({ "id": 1 }),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn deliveries_to_disabled_or_missing_endpoints_are_not_retried() {
+        let mut endpoint = WebhookEndpoint::new("https://example.com/hooks", "secret");
+
+        endpoint.enabled = false;
+        webhook_store().save_endpoint(endpoint.clone()).unwrap();
+
+        let backend = TestBackend::new();
+        let disabled = backend.enqueue(delivery(&endpoint.id));
+        let missing = backend.enqueue(delivery("missing"));
+
+        let run = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(
+                JobHarness::new()
+                    .register_job(&backend, deliver_webhook)
+                    .run_until_idle(),
+            );
+
+        let disabled = backend.outcome(&disabled).unwrap();
+        let missing = backend.outcome(&missing).unwrap();
+
+        assert_eq!(disabled.attempts, 1);
+        assert!(disabled.error.unwrap().contains("is disabled"));
+        assert_eq!(missing.attempts, 1);
+        assert!(missing.error.unwrap().contains("does not exist"));
+        assert!(backend.retry_delays().is_empty());
+        assert!(webhook_store().delivery_logs(&endpoint.id).unwrap().is_empty());
+        assert!(run.has_event(WebhookDelivery::NAME, &WorkerEvent::Idle));
+    }
+
+    #[test]
+    fn deliveries_to_private_hosts_are_blocked_and_counted_once() {
+        let endpoint = WebhookEndpoint::new("https://127.0.0.1/hooks", "secret");
+
+        assert!(register_webhook_endpoint(endpoint.clone()).is_err());
+
+        webhook_store().save_endpoint(endpoint.clone()).unwrap();
+
+        let backend = TestBackend::new();
+        let task_id = backend.enqueue(delivery(&endpoint.id));
+
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(
+                JobHarness::new()
+                    .register_job(&backend, deliver_webhook)
+                    .run_until_idle(),
+            );
+
+        let outcome = backend.outcome(&task_id).unwrap();
+        let logs = webhook_store().delivery_logs(&endpoint.id).unwrap();
+
+        assert_eq!(outcome.attempts, 1);
+        assert!(backend.retry_delays().is_empty());
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].error.as_ref().unwrap().contains("is not a public address"));
+        assert_eq!(
+            webhook_store()
+                .endpoint(&endpoint.id)
+                .unwrap()
+                .unwrap()
+                .consecutive_failures,
+            1
+        );
+    }
+
+    #[test]
+    fn memory_store_disables_endpoints_after_consecutive_failures() {
+        let store = MemoryWebhookStore::new();
+        let endpoint = WebhookEndpoint::new("https://example.com/hooks", "secret");
+
+        store.save_endpoint(endpoint.clone()).unwrap();
+
+        for _ in 1..WEBHOOK_CONFIG.disable_after_failures {
+            store.record_result(&endpoint.id, false).unwrap();
+        }
+
+        assert_eq!(
+            store
+                .record_result(&endpoint.id, true)
+                .unwrap()
+                .unwrap()
+                .consecutive_failures,
+            0
+        );
+
+        for _ in 0..WEBHOOK_CONFIG.disable_after_failures {
+            store.record_result(&endpoint.id, false).unwrap();
+        }
+
+        let endpoint = store.endpoint(&endpoint.id).unwrap().unwrap();
+
+        assert!(!endpoint.enabled);
+        assert!(endpoint.disabled_at.is_some());
+        assert!(store.record_result("missing", false).unwrap().is_none());
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use apalis::prelude::BoxDynError;
+
+use crate::core::config::WEBHOOK_CONFIG;
+
+use super::{WebhookDeliveryLog, WebhookEndpoint};
+
+static WEBHOOK_STORE: OnceLock<Arc<dyn WebhookStore>> = OnceLock::new();
+
+pub trait WebhookStore: Send + Sync + 'static {
+    fn endpoint(&self, id: &str) -> Result<Option<WebhookEndpoint>, BoxDynError>;
+
+    fn endpoints(&self) -> Result<Vec<WebhookEndpoint>, BoxDynError>;
+
+    fn save_endpoint(&self, endpoint: WebhookEndpoint) -> Result<(), BoxDynError>;
+
+    fn remove_endpoint(&self, id: &str) -> Result<Option<WebhookEndpoint>, BoxDynError>;
+
+    fn record_result(&self, endpoint_id: &str, success: bool) -> Result<Option<WebhookEndpoint>, BoxDynError>;
+
+    fn push_delivery_log(&self, log: WebhookDeliveryLog) -> Result<(), BoxDynError>;
+
+    fn delivery_logs(&self, endpoint_id: &str) -> Result<Vec<WebhookDeliveryLog>, BoxDynError>;
+}
+
+pub struct MemoryWebhookStore {
+    log_capacity: usize,
+    disable_after_failures: usize,
+    endpoints: Mutex<BTreeMap<String, WebhookEndpoint>>,
+    logs: Mutex<BTreeMap<String, VecDeque<WebhookDeliveryLog>>>,
+}
+
+impl Default for MemoryWebhookStore {
+    fn default() -> Self {
+        Self::with_log_capacity(WEBHOOK_CONFIG.delivery_log_size)
+    }
+}
+
+impl MemoryWebhookStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_log_capacity(log_capacity: usize) -> Self {
+        Self {
+            log_capacity,
+            disable_after_failures: WEBHOOK_CONFIG.disable_after_failures,
+            endpoints: Mutex::new(BTreeMap::new()),
+            logs: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl WebhookStore for MemoryWebhookStore {
+    fn endpoint(&self, id: &str) -> Result<Option<WebhookEndpoint>, BoxDynError> {
+        Ok(self.endpoints.lock().map_err(|err| err.to_string())?.get(id).cloned())
+    }
+
+    fn endpoints(&self) -> Result<Vec<WebhookEndpoint>, BoxDynError> {
+        Ok(self
+            .endpoints
+            .lock()
+            .map_err(|err| err.to_string())?
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    fn save_endpoint(&self, endpoint: WebhookEndpoint) -> Result<(), BoxDynError> {
+        self.endpoints
+            .lock()
+            .map_err(|err| err.to_string())?
+            .insert(endpoint.id.clone(), endpoint);
+
+        Ok(())
+    }
+
+    fn remove_endpoint(&self, id: &str) -> Result<Option<WebhookEndpoint>, BoxDynError> {
+        self.logs.lock().map_err(|err| err.to_string())?.remove(id);
+
+        Ok(self.endpoints.lock().map_err(|err| err.to_string())?.remove(id))
+    }
+
+    fn record_result(&self, endpoint_id: &str, success: bool) -> Result<Option<WebhookEndpoint>, BoxDynError> {
+        let mut endpoints = self.endpoints.lock().map_err(|err| err.to_string())?;
+
+        Ok(endpoints.get_mut(endpoint_id).map(|endpoint| {
+            endpoint.record_result(success, self.disable_after_failures);
+            endpoint.clone()
+        }))
+    }
+
+    fn push_delivery_log(&self, log: WebhookDeliveryLog) -> Result<(), BoxDynError> {
+        let mut logs = self.logs.lock().map_err(|err| err.to_string())?;
+        let endpoint_logs = logs.entry(log.endpoint_id.clone()).or_default();
+
+        while endpoint_logs.len() >= self.log_capacity.max(1) {
+            endpoint_logs.pop_front();
+        }
+
+        endpoint_logs.push_back(log);
+
+        Ok(())
+    }
+
+    fn delivery_logs(&self, endpoint_id: &str) -> Result<Vec<WebhookDeliveryLog>, BoxDynError> {
+        Ok(self
+            .logs
+            .lock()
+            .map_err(|err| err.to_string())?
+            .get(endpoint_id)
+            .map(|logs| logs.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+pub fn set_webhook_store(store: impl WebhookStore) -> bool {
+    WEBHOOK_STORE.set(Arc::new(store)).is_ok()
+}
+
+pub fn webhook_store() -> Arc<dyn WebhookStore> {
+    WEBHOOK_STORE
+        .get_or_init(|| Arc::new(MemoryWebhookStore::default()))
+        .clone()
+}
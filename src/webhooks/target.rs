@@ -0,0 +1,157 @@
+use std::fmt::{self, Display};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use reqwest::redirect::Policy;
+use url::{Host, Url};
+
+use crate::core::config::WEBHOOK_CONFIG;
+
+#[derive(Debug)]
+pub enum WebhookUrlError {
+    Invalid(url::ParseError),
+    InsecureScheme(String),
+    MissingHost,
+    BlockedHost(String),
+    Resolve(std::io::Error),
+    Client(reqwest::Error),
+}
+
+impl Display for WebhookUrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(error) => write!(f, "Invalid webhook URL: {error}"),
+            Self::InsecureScheme(scheme) => write!(f, "Webhook URLs must use https, not {scheme}"),
+            Self::MissingHost => f.write_str("Webhook URL has no host"),
+            Self::BlockedHost(host) => write!(f, "Webhook host {host} is not a public address"),
+            Self::Resolve(error) => write!(f, "Could not resolve webhook host: {error}"),
+            Self::Client(error) => write!(f, "Could not build webhook client: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for WebhookUrlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Invalid(error) => Some(error),
+            Self::Resolve(error) => Some(error),
+            Self::Client(error) => Some(error),
+            Self::InsecureScheme(_) | Self::MissingHost | Self::BlockedHost(_) => None,
+        }
+    }
+}
+
+pub fn validate_webhook_url(url: &str) -> Result<Url, WebhookUrlError> {
+    let url = Url::parse(url).map_err(WebhookUrlError::Invalid)?;
+
+    if url.scheme() != "https" {
+        return Err(WebhookUrlError::InsecureScheme(url.scheme().to_owned()));
+    }
+
+    match url.host() {
+        None => return Err(WebhookUrlError::MissingHost),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+
+            if domain == "localhost" || domain.ends_with(".localhost") {
+                return Err(WebhookUrlError::BlockedHost(domain));
+            }
+        }
+        Some(Host::Ipv4(ip)) if !is_public_ip(IpAddr::V4(ip)) => {
+            return Err(WebhookUrlError::BlockedHost(ip.to_string()));
+        }
+        Some(Host::Ipv6(ip)) if !is_public_ip(IpAddr::V6(ip)) => {
+            return Err(WebhookUrlError::BlockedHost(ip.to_string()));
+        }
+        Some(_) => {}
+    }
+
+    Ok(url)
+}
+
+pub async fn webhook_client(url: &str) -> Result<(reqwest::Client, Url), WebhookUrlError> {
+    let url = validate_webhook_url(url)?;
+    let host = url.host_str().ok_or(WebhookUrlError::MissingHost)?;
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs = tokio::net::lookup_host((host.trim_matches(['[', ']']), port))
+        .await
+        .map_err(WebhookUrlError::Resolve)?
+        .collect::<Vec<SocketAddr>>();
+
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(WebhookUrlError::BlockedHost(addr.ip().to_string()));
+    }
+
+    let mut builder = reqwest::Client::builder()
+        .redirect(Policy::none())
+        .timeout(WEBHOOK_CONFIG.timeout());
+
+    if let Some(domain) = url.domain() {
+        builder = builder.resolve_to_addrs(domain, &addrs);
+    }
+
+    Ok((builder.build().map_err(WebhookUrlError::Client)?, url))
+}
+
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || first == 0
+        || first >= 240
+        || (first == 100 && (64..128).contains(&second))
+        || (first == 198 && (18..20).contains(&second)))
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn webhook_urls_must_be_https_and_public() {
+        assert!(validate_webhook_url("https://example.com/hooks").is_ok());
+        assert!(validate_webhook_url("https://93.184.216.34/hooks").is_ok());
+
+        for url in [
+            "http://example.com/hooks",
+            "https://localhost/hooks",
+            "https://api.localhost/hooks",
+            "https://127.0.0.1/hooks",
+            "https://10.0.0.1/hooks",
+            "https://172.16.0.1/hooks",
+            "https://192.168.1.1/hooks",
+            "https://169.254.169.254/latest/meta-data",
+            "https://[::1]/hooks",
+            "https://[fe80::1]/hooks",
+            "https://[::ffff:10.0.0.1]/hooks",
+        ] {
+            assert!(validate_webhook_url(url).is_err(), "{url} should be rejected");
+        }
+    }
+}